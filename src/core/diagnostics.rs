@@ -0,0 +1,111 @@
+use crate::core::token::Span;
+use std::fmt;
+
+// An error, ready to be shown to a human: what went wrong, where, and possibly how to fix it.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub title: &'static str,
+    pub message: String,
+    pub span: Span,
+    pub help: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn new(title: &'static str, message: String, span: Span) -> Self {
+        Diagnostic {
+            title,
+            message,
+            span,
+            help: None,
+        }
+    }
+
+    pub fn with_help(mut self, help: Option<String>) -> Self {
+        self.help = help;
+        self
+    }
+
+    // Renders the diagnostic along with the line of source code it points at:
+    //
+    //   error: undefined variable `abd`
+    //    --> main.al:2:10
+    //     |
+    //   2 | println(abd);
+    //     |         ^^^
+    //     = help: did you mean `abc`?
+    pub fn render(&self, file_name: &str, source: &str) -> String {
+        let mut output = format!("{}: {}\n", self.title, self.message);
+        let line_number = self.span.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+        output.push_str(&format!(
+            "{}--> {}:{}:{}\n",
+            gutter, file_name, self.span.line, self.span.column
+        ));
+
+        let line = self
+            .span
+            .line
+            .checked_sub(1)
+            .and_then(|index| source.lines().nth(index));
+        if let Some(line) = line {
+            // Tabs are kept, so that the caret lines up however wide they are shown.
+            let padding: String = line
+                .chars()
+                .take(self.span.column.saturating_sub(1))
+                .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+                .collect();
+            let width = source
+                .get(self.span.start..self.span.end)
+                .and_then(|text| text.lines().next())
+                .map_or(1, |text| text.chars().count().max(1));
+
+            output.push_str(&format!("{} |\n", gutter));
+            output.push_str(&format!("{} | {}\n", line_number, line));
+            output.push_str(&format!("{} | {}{}\n", gutter, padding, "^".repeat(width)));
+        }
+
+        if let Some(help) = &self.help {
+            output.push_str(&format!("{} = help: {}\n", gutter, help));
+        }
+        output
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} at {}:{}",
+            self.message, self.span.line, self.span.column
+        )
+    }
+}
+
+// The candidate closest to name, if it is close enough to be a likely typo.
+pub fn closest_match<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<String> {
+    let max_distance = (name.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min()
+        .map(|(_, candidate)| candidate.to_string())
+}
+
+// The Levenshtein distance between a and b.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_ch) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_ch) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_ch != *b_ch);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
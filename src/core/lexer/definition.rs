@@ -1,46 +1,129 @@
-use crate::core::lexer::token::Token;
+use std::fmt;
+
+use crate::core::diagnostics::Diagnostic;
+use crate::core::lexer::token::{Span, Token, TokenKind};
 use crate::core::lexer::tokens::{
-    AND, ASSIGN, ASTERISK, BANG, COMMA, DOUBLE_QUOTES, ELSE, EQ, FALSE, FOR, FUNCTION, GT, GT_EQ,
-    IF, LBRACE, LBRACKET, LET, LPAREN, LT, LT_EQ, MINUS, NOT_EQ, OR, PLUS, RBRACE, RBRACKET,
-    RETURN, RPAREN, SEMICOLON, SLASH, TRUE,
+    AMPERSAND, AND, ASSIGN, ASTERISK, BACKSLASH, BANG, BREAK, CARET, COLON, COMMA, CONTINUE,
+    DOUBLE_QUOTES, ELSE, EQ, FALSE, FOR, FUNCTION, GT, GT_EQ, IF, LBRACE, LBRACKET, LET, LPAREN,
+    LT, LT_EQ, MINUS, NOT_EQ, OR, PERCENT, PIPE, PLUS, POWER, RBRACE, RBRACKET, RETURN, RPAREN,
+    SEMICOLON, SHIFT_LEFT, SHIFT_RIGHT, SLASH, TILDE, TRUE, WHILE,
 };
 
 pub struct Lexer<'lifetime_input> {
     input: &'lifetime_input str,
-    line_number: usize,
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+#[derive(Debug, Clone)]
+pub enum LexError {
+    UnterminatedString { span: Span },
+    UnterminatedComment { span: Span },
+    InvalidCharacter { character: char, span: Span },
+    InvalidEscape { message: String, span: Span },
+    MalformedNumber { literal: String, span: Span },
+    NumberOutOfRange { literal: String, span: Span },
+}
+
+impl LexError {
+    pub fn span(&self) -> Span {
+        match self {
+            LexError::UnterminatedString { span }
+            | LexError::UnterminatedComment { span }
+            | LexError::InvalidCharacter { span, .. }
+            | LexError::InvalidEscape { span, .. }
+            | LexError::MalformedNumber { span, .. }
+            | LexError::NumberOutOfRange { span, .. } => *span,
+        }
+    }
+}
+
+impl LexError {
+    pub fn diagnostic(&self) -> Diagnostic {
+        let message = match self {
+            LexError::UnterminatedString { .. } => "unterminated string literal".to_string(),
+            LexError::UnterminatedComment { .. } => "unterminated block comment".to_string(),
+            LexError::InvalidCharacter { character, .. } => {
+                format!("invalid character `{}`", character)
+            }
+            LexError::InvalidEscape { message, .. } => message.clone(),
+            LexError::MalformedNumber { literal, .. } => format!("malformed number `{}`", literal),
+            LexError::NumberOutOfRange { literal, .. } => {
+                format!("number `{}` is out of range", literal)
+            }
+        };
+        Diagnostic::new("syntax error", message, self.span())
+    }
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.diagnostic())
+    }
 }
 
+impl std::error::Error for LexError {}
+
 impl<'lifetime_input> Lexer<'lifetime_input> {
     pub fn new(input: &'lifetime_input str) -> Self {
         Lexer {
             input,
-            line_number: 0,
+            offset: 0,
+            line: 1,
+            column: 1,
         }
     }
 
-    pub fn tokenize(mut self) -> Vec<Token> {
+    pub fn tokenize(mut self) -> Result<Vec<Token>, LexError> {
         let mut tokens: Vec<Token> = Vec::new();
         let mut is_literal = false;
-        let mut is_token_numeric = false;
         let mut token_string = String::new();
-        let mut chars = self.input.chars().peekable();
-        while let Some(ch) = chars.next() {
-            if ch == '\n' {
-                self.line_number += 1;
+        // Where the token that is being collected in token_string started.
+        let mut token_start = self.here();
+        loop {
+            let ch_start = self.here();
+            let Some(ch) = self.next_char() else {
+                break;
+            };
+
+            // Escape sequences have to be handled before the double quote, so that \" doesn't end
+            // the string literal.
+            if is_literal && ch == BACKSLASH {
+                match self.read_escape_sequence() {
+                    Ok(escaped) => token_string.push(escaped),
+                    Err(message) => {
+                        return Err(LexError::InvalidEscape {
+                            message,
+                            span: self.span_from(ch_start),
+                        })
+                    }
+                }
+                continue;
             }
+
+            // Raw strings look like r"C:\path" or r#"say "hi""#, the hashes allow double quotes
+            // inside the string. Nothing inside of them is escaped.
+            if ch == DOUBLE_QUOTES && !is_literal && Self::is_raw_string_prefix(&token_string) {
+                let hashes = token_string.len() - 1;
+                token_string.clear();
+                tokens.push(self.read_raw_string(token_start, hashes)?);
+                continue;
+            }
+
             // If ch is a double quote, handle that as string
             if ch == DOUBLE_QUOTES {
-                // if its new string literal, then we should set is_literal to true and empty the token_string
+                // if its new string literal, then we should set is_literal to true
                 // if its closing the string literal, then we should push the string literal to the tokens
                 if !is_literal {
+                    Self::push_token(&mut tokens, &mut token_string, token_start.until(ch_start))?;
                     is_literal = true;
-                    token_string = String::new();
+                    token_start = ch_start;
                 } else {
                     is_literal = false;
                     tokens.push(Token::new(
-                        "STRING".to_string(),
-                        token_string.to_string(),
-                        self.line_number,
+                        TokenKind::String(token_string.to_string()),
+                        self.span_from(token_start),
                     ));
                     token_string.clear();
                 }
@@ -48,174 +131,376 @@ impl<'lifetime_input> Lexer<'lifetime_input> {
             }
 
             // If its a literal, then we should push the character to the string literal. We don't really care what the character is as long as its not a double quote.
-            // We don't handle escape characters yet.
             if is_literal {
                 token_string.push(ch);
                 continue;
             }
 
+            // Comments end whatever token came before them and are dropped entirely.
+            if ch == SLASH && matches!(self.peek_char(), Some(SLASH) | Some(ASTERISK)) {
+                Self::push_token(&mut tokens, &mut token_string, token_start.until(ch_start))?;
+                if self.next_char() == Some(SLASH) {
+                    while self.next_char_if(|next_ch| next_ch != '\n').is_some() {}
+                } else {
+                    self.skip_block_comment(ch_start)?;
+                }
+                continue;
+            }
+
             // If ch is whitespace or semicolon, then we should push the token to the tokens vector and continue
             if ch.is_whitespace() || ch == SEMICOLON {
-                // if is_token_numeric, then we push to tokens as number else as identified token
-                Self::push_token(
-                    &mut tokens,
-                    &mut is_token_numeric,
-                    &mut token_string,
-                    self.line_number,
-                );
-                is_token_numeric = false;
+                Self::push_token(&mut tokens, &mut token_string, token_start.until(ch_start))?;
                 if ch == SEMICOLON {
-                    tokens.push(Token::new(
-                        "SEMICOLON".to_string(),
-                        ";".to_string(),
-                        self.line_number,
-                    ));
+                    tokens.push(Token::new(TokenKind::Semicolon, self.span_from(ch_start)));
                 }
                 continue;
             }
 
-            // If the token_string is a numeric, then we should push the `.` character to it.
-            if is_token_numeric {
-                if ch == '.' || ch.is_numeric() {
-                    token_string.push(ch);
+            // A digit can only start a number, identifiers may contain digits but not start with
+            // them.
+            if token_string.is_empty() && ch.is_ascii_digit() {
+                tokens.push(self.read_number(ch, ch_start)?);
+                continue;
+            }
+
+            // Operators like <=, >=, ==, &&, ||, ** and << are made of two characters, so they
+            // have to be checked before the single valued tokens.
+            if let Some(next_ch) = self.peek_char() {
+                if let Some(compound_token_kind) = Self::get_compound_token(ch, next_ch) {
+                    Self::push_token(&mut tokens, &mut token_string, token_start.until(ch_start))?;
+                    self.next_char();
+                    tokens.push(Token::new(compound_token_kind, self.span_from(ch_start)));
                     continue;
                 }
+            }
 
-                is_token_numeric = false;
-                tokens.push(Token::new(
-                    "NUMBER".to_string(),
-                    token_string.clone(),
-                    self.line_number,
-                ));
-                token_string.clear();
+            // If its a single valued token like +, -, etc, then we should push the token to the tokens vector and continue
+            if let Some(token_kind) = Self::get_single_valued_token(ch) {
+                Self::push_token(&mut tokens, &mut token_string, token_start.until(ch_start))?;
+                tokens.push(Token::new(token_kind, self.span_from(ch_start)));
+                continue;
             }
 
-            // At this point we know that its not string literal we are working with.
+            // Whatever is left has to be part of an identifier or a keyword. Hashes are only
+            // allowed as the prefix of a raw string, push_token rejects them otherwise.
+            if ch.is_alphanumeric()
+                || ch == '_'
+                || (ch == '#' && Self::is_raw_string_prefix(&token_string))
+            {
+                if token_string.is_empty() {
+                    token_start = ch_start;
+                }
+                token_string.push(ch);
+                continue;
+            }
 
-            // If its a single valued token like +, -, etc, then we should push the token to the tokens vector and continue
-            if let Some(token_type) = Self::get_single_valued_token(ch) {
-                // Although its a single value token, we need to check if the next character is characters like =, etc so that we can handle <=, >=, ==, etc
-                if let Some(next_ch) = chars.peek() {
-                    // if there are any token bore this some next_ch, just add it.
-                    if Self::handle_compound_operator(&mut tokens, ch, next_ch, self.line_number) {
-                        chars.next();
-                        continue;
-                    }
+            return Err(LexError::InvalidCharacter {
+                character: ch,
+                span: self.span_from(ch_start),
+            });
+        }
+
+        if is_literal {
+            return Err(LexError::UnterminatedString {
+                span: token_start.until(self.here()),
+            });
+        }
+
+        Self::push_token(
+            &mut tokens,
+            &mut token_string,
+            token_start.until(self.here()),
+        )?;
+
+        Ok(tokens)
+    }
+
+    // The position of the next character, as an empty span.
+    fn here(&self) -> Span {
+        Span::new(self.offset, self.offset, self.line, self.column)
+    }
+
+    // The span from start up to the last character that was read.
+    fn span_from(&self, start: Span) -> Span {
+        start.until(self.here())
+    }
+
+    fn peek_char(&self) -> Option<char> {
+        self.input[self.offset..].chars().next()
+    }
+
+    fn next_char(&mut self) -> Option<char> {
+        let ch = self.peek_char()?;
+        self.offset += ch.len_utf8();
+        if ch == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(ch)
+    }
+
+    fn next_char_if(&mut self, predicate: impl Fn(char) -> bool) -> Option<char> {
+        match self.peek_char() {
+            Some(ch) if predicate(ch) => self.next_char(),
+            _ => None,
+        }
+    }
+
+    // Reads what follows a backslash inside a string literal. Returns a description of the
+    // problem for unknown or malformed escapes.
+    fn read_escape_sequence(&mut self) -> Result<char, String> {
+        match self.next_char() {
+            Some('n') => Ok('\n'),
+            Some('t') => Ok('\t'),
+            Some('r') => Ok('\r'),
+            Some('0') => Ok('\0'),
+            Some(BACKSLASH) => Ok(BACKSLASH),
+            Some(DOUBLE_QUOTES) => Ok(DOUBLE_QUOTES),
+            Some('u') => {
+                if self.next_char_if(|next_ch| next_ch == LBRACE).is_none() {
+                    return Err("expected { after \\u".to_string());
                 }
+                let mut hex_digits = String::new();
+                while let Some(digit) = self.next_char_if(|next_ch| next_ch.is_ascii_hexdigit()) {
+                    hex_digits.push(digit);
+                }
+                if self.next_char_if(|next_ch| next_ch == RBRACE).is_none() || hex_digits.is_empty()
+                {
+                    return Err("malformed unicode escape, expected \\u{...}".to_string());
+                }
+                u32::from_str_radix(&hex_digits, 16)
+                    .ok()
+                    .filter(|_| hex_digits.len() <= 6)
+                    .and_then(char::from_u32)
+                    .ok_or(format!("invalid unicode escape \\u{{{}}}", hex_digits))
+            }
+            Some(other) => Err(format!("unknown escape sequence \\{}", other)),
+            None => Err("unterminated escape sequence".to_string()),
+        }
+    }
+
+    fn is_raw_string_prefix(token_string: &str) -> bool {
+        token_string.starts_with('r') && token_string[1..].chars().all(|ch| ch == '#')
+    }
 
-                Self::push_token(
-                    &mut tokens,
-                    &mut is_token_numeric,
-                    &mut token_string,
-                    self.line_number,
-                );
-                tokens.push(Token::new(
-                    token_type.to_string(),
-                    ch.to_string(),
-                    self.line_number,
-                ));
+    // Reads a raw string whose opening quote has already been consumed. It ends at a double quote
+    // followed by as many hashes as it was opened with.
+    fn read_raw_string(&mut self, start: Span, hashes: usize) -> Result<Token, LexError> {
+        let mut literal = String::new();
+        while let Some(ch) = self.next_char() {
+            if ch == DOUBLE_QUOTES {
+                let mut closing_hashes = 0;
+                while closing_hashes < hashes
+                    && self.next_char_if(|next_ch| next_ch == '#').is_some()
+                {
+                    closing_hashes += 1;
+                }
+                if closing_hashes == hashes {
+                    return Ok(Token::new(
+                        TokenKind::String(literal),
+                        self.span_from(start),
+                    ));
+                }
+                literal.push(ch);
+                literal.push_str(&"#".repeat(closing_hashes));
                 continue;
             }
 
-            // At this point we know ch is not whitespace nor semicolon, so we should handle other cases
-            if ch.is_numeric() {
-                is_token_numeric = true;
-                token_string.push(ch);
-                continue;
+            literal.push(ch);
+        }
+
+        Err(LexError::UnterminatedString {
+            span: self.span_from(start),
+        })
+    }
+
+    // Reads a number whose first digit has already been consumed. Everything that could belong to
+    // the number is read, so that `1.2.3` or `12ab` are reported as a whole. Integers can be
+    // written in hex (0xFF), binary (0b1010) or octal (0o17), decimals can have an exponent
+    // (1.5e-3), and both can use `_` between digits. Integers end up in the token as plain decimal
+    // digits, so the rest of the interpreter doesn't have to know about any of this.
+    fn read_number(&mut self, first_digit: char, start: Span) -> Result<Token, LexError> {
+        let mut literal = first_digit.to_string();
+        while let Some(ch) = self
+            .next_char_if(|next_ch| next_ch.is_alphanumeric() || next_ch == '.' || next_ch == '_')
+        {
+            literal.push(ch);
+            // The sign of an exponent belongs to the number, but 0x1e-3 is a subtraction.
+            if matches!(ch, 'e' | 'E') && !literal.starts_with("0x") && !literal.starts_with("0X") {
+                if let Some(sign) = self.next_char_if(|next_ch| next_ch == '+' || next_ch == '-') {
+                    literal.push(sign);
+                }
             }
+        }
+
+        let span = self.span_from(start);
+        let (radix, digits) = match literal.get(..2) {
+            Some("0x" | "0X") => (16, &literal[2..]),
+            Some("0b" | "0B") => (2, &literal[2..]),
+            Some("0o" | "0O") => (8, &literal[2..]),
+            _ => (10, literal.as_str()),
+        };
+
+        // Separators are only allowed between two digits, `1_000` is fine but `1__0` or `1_.5` aren't.
+        let chars: Vec<char> = digits.chars().collect();
+        let separators_are_valid = chars.iter().enumerate().all(|(index, ch)| {
+            *ch != '_'
+                || (index > 0
+                    && chars[index - 1].is_digit(radix)
+                    && chars
+                        .get(index + 1)
+                        .is_some_and(|next_ch| next_ch.is_digit(radix)))
+        });
+        let digits = digits.replace('_', "");
+        if digits.is_empty() || !separators_are_valid {
+            return Err(LexError::MalformedNumber { literal, span });
+        }
 
-            // If ch is not a number, then we should push the character to the token_string
-            token_string.push(ch);
+        if radix != 10 || digits.chars().all(|ch| ch.is_ascii_digit()) {
+            if !digits.chars().all(|ch| ch.is_digit(radix)) {
+                return Err(LexError::MalformedNumber { literal, span });
+            }
+            // The digits are valid, so the only way left for this to fail is an overflow.
+            return match i64::from_str_radix(&digits, radix) {
+                Ok(value) => Ok(Token::new(TokenKind::Number(value.to_string()), span)),
+                Err(_) => Err(LexError::NumberOutOfRange { literal, span }),
+            };
         }
 
-        tokens
+        if !Self::is_decimal_literal(&digits) {
+            return Err(LexError::MalformedNumber { literal, span });
+        }
+        match digits.parse::<f64>() {
+            Ok(value) if value.is_finite() => Ok(Token::new(TokenKind::Number(digits), span)),
+            _ => Err(LexError::NumberOutOfRange { literal, span }),
+        }
     }
 
-    fn handle_compound_operator(
-        tokens: &mut Vec<Token>,
-        ch: char,
-        next_ch: &char,
-        line_number: usize,
-    ) -> bool {
-        let compound_token = format!("{}{}", ch, next_ch);
-        let compound_token_type = match compound_token.as_str() {
-            EQ => "EQ",
-            NOT_EQ => "NOT_EQ",
-            LT_EQ => "LT_EQ",
-            GT_EQ => "GT_EQ",
-            OR => "OR",
-            AND => "AND",
-            _ => "",
+    // Whether the literal looks like 1.5, 15e3 or 1.5E-3, separators already removed.
+    fn is_decimal_literal(literal: &str) -> bool {
+        let is_digits = |part: &str| !part.is_empty() && part.chars().all(|ch| ch.is_ascii_digit());
+        let (mantissa, exponent) = match literal.split_once(['e', 'E']) {
+            Some((mantissa, exponent)) => (mantissa, Some(exponent)),
+            None => (literal, None),
         };
+        let (whole, fraction) = match mantissa.split_once('.') {
+            Some((whole, fraction)) => (whole, Some(fraction)),
+            None => (mantissa, None),
+        };
+
+        is_digits(whole)
+            && fraction.is_none_or(is_digits)
+            && exponent.is_none_or(|exponent| {
+                is_digits(exponent.strip_prefix(['+', '-']).unwrap_or(exponent))
+            })
+    }
 
-        if compound_token_type.is_empty() {
-            return false;
+    // Skips a block comment whose opening `/*` has already been consumed. Block comments nest, so
+    // `/* a /* b */ c */` is a single comment.
+    fn skip_block_comment(&mut self, start: Span) -> Result<(), LexError> {
+        let mut depth = 1;
+        while let Some(ch) = self.next_char() {
+            match ch {
+                SLASH if self.next_char_if(|next_ch| next_ch == ASTERISK).is_some() => depth += 1,
+                ASTERISK if self.next_char_if(|next_ch| next_ch == SLASH).is_some() => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Err(LexError::UnterminatedComment {
+            span: self.span_from(start),
+        })
+    }
+
+    fn get_compound_token(ch: char, next_ch: char) -> Option<TokenKind> {
+        match format!("{}{}", ch, next_ch).as_str() {
+            EQ => Some(TokenKind::Eq),
+            NOT_EQ => Some(TokenKind::NotEq),
+            LT_EQ => Some(TokenKind::LtEq),
+            GT_EQ => Some(TokenKind::GtEq),
+            OR => Some(TokenKind::Or),
+            AND => Some(TokenKind::And),
+            POWER => Some(TokenKind::Power),
+            SHIFT_LEFT => Some(TokenKind::ShiftLeft),
+            SHIFT_RIGHT => Some(TokenKind::ShiftRight),
+            _ => None,
         }
-        tokens.push(Token::new(
-            String::from(compound_token_type),
-            compound_token,
-            line_number,
-        ));
-        true
     }
 
     fn push_token(
         tokens: &mut Vec<Token>,
-        is_token_numeric: &mut bool,
         token_string: &mut String,
-        line_number: usize,
-    ) {
-        if *is_token_numeric && !token_string.is_empty() {
-            tokens.push(Token::new(
-                "NUMBER".to_string(),
-                token_string.clone(),
-                line_number,
-            ));
-            token_string.clear();
-            *is_token_numeric = false;
-        } else if !token_string.is_empty() {
-            let mut identified_token = Self::get_identified_token(token_string);
-            identified_token.line_number = line_number;
-            tokens.push(identified_token);
-            token_string.clear();
-        }
-    }
-
-    fn get_single_valued_token(ch: char) -> Option<&'static str> {
+        span: Span,
+    ) -> Result<(), LexError> {
+        if token_string.is_empty() {
+            return Ok(());
+        }
+        // A hash is only valid right before the opening quote of a raw string.
+        if let Some(position) = token_string.find('#') {
+            return Err(LexError::InvalidCharacter {
+                character: '#',
+                span: Span::new(
+                    span.start + position,
+                    span.start + position + 1,
+                    span.line,
+                    span.column + token_string[..position].chars().count(),
+                ),
+            });
+        }
+        tokens.push(Self::get_identified_token(token_string, span));
+        token_string.clear();
+        Ok(())
+    }
+
+    fn get_single_valued_token(ch: char) -> Option<TokenKind> {
         match ch {
-            PLUS => Some("PLUS"),
-            MINUS => Some("MINUS"),
-            BANG => Some("BANG"),
-            ASTERISK => Some("ASTERISK"),
-            SLASH => Some("SLASH"),
-            ASSIGN => Some("ASSIGN"),
-            LPAREN => Some("LPAREN"),
-            RPAREN => Some("RPAREN"),
-            LBRACE => Some("LBRACE"),
-            RBRACE => Some("RBRACE"),
-            LBRACKET => Some("LBRACKET"),
-            RBRACKET => Some("RBRACKET"),
-            LT => Some("LT"),
-            GT => Some("GT"),
-            COMMA => Some("COMMA"),
+            PLUS => Some(TokenKind::Plus),
+            MINUS => Some(TokenKind::Minus),
+            BANG => Some(TokenKind::Bang),
+            ASTERISK => Some(TokenKind::Asterisk),
+            SLASH => Some(TokenKind::Slash),
+            PERCENT => Some(TokenKind::Percent),
+            AMPERSAND => Some(TokenKind::Ampersand),
+            PIPE => Some(TokenKind::Pipe),
+            CARET => Some(TokenKind::Caret),
+            TILDE => Some(TokenKind::Tilde),
+            ASSIGN => Some(TokenKind::Assign),
+            LPAREN => Some(TokenKind::LParen),
+            RPAREN => Some(TokenKind::RParen),
+            LBRACE => Some(TokenKind::LBrace),
+            RBRACE => Some(TokenKind::RBrace),
+            LBRACKET => Some(TokenKind::LBracket),
+            RBRACKET => Some(TokenKind::RBracket),
+            LT => Some(TokenKind::Lt),
+            GT => Some(TokenKind::Gt),
+            COMMA => Some(TokenKind::Comma),
+            COLON => Some(TokenKind::Colon),
             _ => None,
         }
     }
 
-    fn get_identified_token(token_string: &mut String) -> Token {
-        let identified_token = match token_string.to_uppercase().as_str() {
-            LET => Token::new_without_line_number(LET.to_string(), token_string.to_string()),
-            FUNCTION => {
-                Token::new_without_line_number(FUNCTION.to_string(), token_string.to_string())
-            }
-            IF => Token::new_without_line_number(IF.to_string(), token_string.to_string()),
-            ELSE => Token::new_without_line_number(ELSE.to_string(), token_string.to_string()),
-            RETURN => Token::new_without_line_number(RETURN.to_string(), token_string.to_string()),
-            TRUE => Token::new_without_line_number(TRUE.to_string(), token_string.to_string()),
-            FALSE => Token::new_without_line_number(FALSE.to_string(), token_string.to_string()),
-            FOR => Token::new_without_line_number(FOR.to_string(), token_string.to_string()),
-            _ => Token::new_without_line_number("IDENTIFIER".to_string(), token_string.to_string()),
+    fn get_identified_token(token_string: &str, span: Span) -> Token {
+        let token_kind = match token_string.to_uppercase().as_str() {
+            LET => TokenKind::Let,
+            FUNCTION => TokenKind::Function,
+            IF => TokenKind::If,
+            ELSE => TokenKind::Else,
+            RETURN => TokenKind::Return,
+            TRUE => TokenKind::True,
+            FALSE => TokenKind::False,
+            FOR => TokenKind::For,
+            WHILE => TokenKind::While,
+            BREAK => TokenKind::Break,
+            CONTINUE => TokenKind::Continue,
+            _ => TokenKind::Identifier(token_string.to_string()),
         };
-        identified_token
+        Token::new(token_kind, span)
     }
 }
@@ -1,25 +1,158 @@
-#[derive(Debug)]
+use std::fmt;
+
+#[derive(Debug, Clone)]
 pub struct Token {
-    pub token_type: String,
-    pub literal: String,
-    pub line_number: usize,
+    pub kind: TokenKind,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    // Keywords
+    Let,
+    Function,
+    If,
+    Else,
+    Return,
+    True,
+    False,
+    For,
+    While,
+    Break,
+    Continue,
+
+    // Identifiers and literals, along with their values
+    Identifier(String),
+    Number(String),
+    String(String),
+
+    // Operators
+    Assign,
+    Plus,
+    Minus,
+    Bang,
+    Asterisk,
+    Slash,
+    Percent,
+    Power,
+    Ampersand,
+    Pipe,
+    Caret,
+    Tilde,
+    ShiftLeft,
+    ShiftRight,
+    Eq,
+    NotEq,
+    LtEq,
+    GtEq,
+    And,
+    Or,
+    Lt,
+    Gt,
+
+    // Delimiters
+    Semicolon,
+    Colon,
+    Comma,
+    LParen,
+    RParen,
+    LBrace,
+    RBrace,
+    LBracket,
+    RBracket,
+}
+
+// Where a piece of source code lives. `start` and `end` are byte offsets into the input, `line`
+// and `column` are where it starts, both counted from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
 }
 
 impl Token {
-    pub fn new(token_type: String, literal: String, line_number: usize) -> Self {
-        Token {
-            token_type,
-            literal,
-            line_number,
-        }
+    pub fn new(kind: TokenKind, span: Span) -> Self {
+        Token { kind, span }
     }
-    pub fn new_without_line_number(token_type: String, literal: String) -> Self {
-        Token {
-            token_type,
-            literal,
-            line_number: 0,
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TokenKind::Let => write!(f, "`let`"),
+            TokenKind::Function => write!(f, "`function`"),
+            TokenKind::If => write!(f, "`if`"),
+            TokenKind::Else => write!(f, "`else`"),
+            TokenKind::Return => write!(f, "`return`"),
+            TokenKind::True => write!(f, "`true`"),
+            TokenKind::False => write!(f, "`false`"),
+            TokenKind::For => write!(f, "`for`"),
+            TokenKind::While => write!(f, "`while`"),
+            TokenKind::Break => write!(f, "`break`"),
+            TokenKind::Continue => write!(f, "`continue`"),
+            TokenKind::Identifier(name) => write!(f, "identifier `{}`", name),
+            TokenKind::Number(number) => write!(f, "number `{}`", number),
+            TokenKind::String(value) => write!(f, "string {:?}", value),
+            TokenKind::Assign => write!(f, "`=`"),
+            TokenKind::Plus => write!(f, "`+`"),
+            TokenKind::Minus => write!(f, "`-`"),
+            TokenKind::Bang => write!(f, "`!`"),
+            TokenKind::Asterisk => write!(f, "`*`"),
+            TokenKind::Slash => write!(f, "`/`"),
+            TokenKind::Percent => write!(f, "`%`"),
+            TokenKind::Power => write!(f, "`**`"),
+            TokenKind::Ampersand => write!(f, "`&`"),
+            TokenKind::Pipe => write!(f, "`|`"),
+            TokenKind::Caret => write!(f, "`^`"),
+            TokenKind::Tilde => write!(f, "`~`"),
+            TokenKind::ShiftLeft => write!(f, "`<<`"),
+            TokenKind::ShiftRight => write!(f, "`>>`"),
+            TokenKind::Eq => write!(f, "`==`"),
+            TokenKind::NotEq => write!(f, "`!=`"),
+            TokenKind::LtEq => write!(f, "`<=`"),
+            TokenKind::GtEq => write!(f, "`>=`"),
+            TokenKind::And => write!(f, "`&&`"),
+            TokenKind::Or => write!(f, "`||`"),
+            TokenKind::Lt => write!(f, "`<`"),
+            TokenKind::Gt => write!(f, "`>`"),
+            TokenKind::Semicolon => write!(f, "`;`"),
+            TokenKind::Colon => write!(f, "`:`"),
+            TokenKind::Comma => write!(f, "`,`"),
+            TokenKind::LParen => write!(f, "`(`"),
+            TokenKind::RParen => write!(f, "`)`"),
+            TokenKind::LBrace => write!(f, "`{{`"),
+            TokenKind::RBrace => write!(f, "`}}`"),
+            TokenKind::LBracket => write!(f, "`[`"),
+            TokenKind::RBracket => write!(f, "`]`"),
         }
     }
 }
 
-// make default line number 0 in new method
+impl Span {
+    pub fn new(start: usize, end: usize, line: usize, column: usize) -> Self {
+        Span {
+            start,
+            end,
+            line,
+            column,
+        }
+    }
+
+    // The span covering both self and everything up to the end of other.
+    pub fn to(self, other: Span) -> Span {
+        Span {
+            end: other.end.max(self.end),
+            ..self
+        }
+    }
+
+    // The span from the start of self up to, but not including, the start of other.
+    pub fn until(self, other: Span) -> Span {
+        Span {
+            end: other.start,
+            ..self
+        }
+    }
+}
@@ -7,6 +7,9 @@ pub const RETURN: &str = "RETURN";
 pub const TRUE: &str = "TRUE";
 pub const FALSE: &str = "FALSE";
 pub const FOR: &str = "FOR";
+pub const WHILE: &str = "WHILE";
+pub const BREAK: &str = "BREAK";
+pub const CONTINUE: &str = "CONTINUE";
 
 // Operators
 pub const ASSIGN: char = '=';
@@ -15,7 +18,13 @@ pub const MINUS: char = '-';
 pub const BANG: char = '!';
 pub const ASTERISK: char = '*';
 pub const SLASH: char = '/';
+pub const PERCENT: char = '%';
+pub const AMPERSAND: char = '&';
+pub const PIPE: char = '|';
+pub const CARET: char = '^';
+pub const TILDE: char = '~';
 pub const DOUBLE_QUOTES: char = '"';
+pub const BACKSLASH: char = '\\';
 
 // Compound Operators
 pub const EQ: &str = "==";
@@ -24,8 +33,12 @@ pub const LT_EQ: &str = "<=";
 pub const GT_EQ: &str = ">=";
 pub const AND: &str = "&&";
 pub const OR: &str = "||";
+pub const POWER: &str = "**";
+pub const SHIFT_LEFT: &str = "<<";
+pub const SHIFT_RIGHT: &str = ">>";
 
 pub const SEMICOLON: char = ';';
+pub const COLON: char = ':';
 
 // For all kinds of brackets
 pub const LPAREN: char = '(';
@@ -1,3 +1,4 @@
+pub mod diagnostics;
 pub mod lexer;
 pub mod parser;
 pub mod semantic_analyzer;
@@ -6,3 +7,4 @@ pub use lexer::token;
 pub use lexer::Lexer;
 pub use parser::Parser;
 pub use semantic_analyzer::Analyzer;
+pub use semantic_analyzer::TypeChecker;
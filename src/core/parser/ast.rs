@@ -1,35 +1,115 @@
 #![allow(dead_code)]
 
-use std::{collections::HashMap, fmt};
+use crate::core::lexer::token::Span;
+use std::fmt;
 
 // ignore dead code
 #[derive(Debug, PartialEq, Clone)]
 pub enum Statement {
-    Let(String, Box<Expression>),         // Represents "let x = 5;"
-    Assignment(String, Box<Expression>),  // Represents "x = 10;"
-    ExpressionStatement(Box<Expression>), // Represents standalone expressions
-    IfStatement(Box<Expression>, Vec<Statement>),
+    Let(String, Option<Type>, Box<Expression>, Span), // Represents "let x: int = 5;"
+    Assignment(String, Box<Expression>, Span),        // Represents "x = 10;"
+    ExpressionStatement(Box<Expression>, Span),       // Represents standalone expressions
+    // The condition, the statements to run when it holds and the optional else branch. An
+    // `else if` is an else branch holding a single IfStatement.
+    IfStatement(
+        Box<Expression>,
+        Vec<Statement>,
+        Option<Vec<Statement>>,
+        Span,
+    ),
     ForStatement(
         Box<Expression>,
         Box<Expression>,
         Box<Expression>,
         Vec<Statement>,
+        Span,
     ),
-    FunctionDeclaration(String, HashMap<String, Type>, Vec<Statement>, Option<Type>),
-    ArrayAssignment(String, Box<Expression>, Box<Expression>),
+    WhileStatement(Box<Expression>, Vec<Statement>, Span),
+    Break(Span),
+    Continue(Span),
+    // The name, the parameters in order, the body and the return type, if any.
+    FunctionDeclaration(String, Vec<Parameter>, Vec<Statement>, Option<Type>, Span),
+    ArrayAssignment(String, Box<Expression>, Box<Expression>, Span),
+    Return(Option<Box<Expression>>, Span),
 }
 
+// Every node remembers where it came from in the source, so that errors can point at it.
 #[derive(Debug, PartialEq, Clone)]
 pub enum Expression {
-    BinOp(Box<Expression>, Op, Box<Expression>, Option<Type>),
-    Identifier(String, Option<Type>),
-    Number(String, Option<Type>),
-    StringLiteral(String, Option<Type>),
-    FunctionCall(String, Vec<Expression>, Option<Type>),
-    UnaryOp(Op, Box<Expression>, Option<Type>),
-    Boolean(bool, Option<Type>),
-    Array(Vec<Expression>, Option<Type>),
-    ArrayAccess(String, Box<Expression>, Option<Type>),
+    BinOp(Box<Expression>, Op, Box<Expression>, Option<Type>, Span),
+    Identifier(String, Option<Type>, Span),
+    Number(String, Option<Type>, Span),
+    StringLiteral(String, Option<Type>, Span),
+    FunctionCall(String, Vec<Expression>, Option<Type>, Span),
+    UnaryOp(Op, Box<Expression>, Option<Type>, Span),
+    Boolean(bool, Option<Type>, Span),
+    Array(Vec<Expression>, Option<Type>, Span),
+    ArrayAccess(String, Box<Expression>, Option<Type>, Span),
+}
+
+impl Statement {
+    pub fn span(&self) -> Span {
+        match self {
+            Statement::Let(_, _, _, span)
+            | Statement::Assignment(_, _, span)
+            | Statement::ExpressionStatement(_, span)
+            | Statement::IfStatement(_, _, _, span)
+            | Statement::ForStatement(_, _, _, _, span)
+            | Statement::WhileStatement(_, _, span)
+            | Statement::Break(span)
+            | Statement::Continue(span)
+            | Statement::FunctionDeclaration(_, _, _, _, span)
+            | Statement::ArrayAssignment(_, _, _, span)
+            | Statement::Return(_, span) => *span,
+        }
+    }
+}
+
+impl Expression {
+    // The type the type checker inferred, None until the expression has been checked.
+    pub fn expression_type(&self) -> Option<&Type> {
+        match self {
+            Expression::BinOp(_, _, _, expression_type, _)
+            | Expression::Identifier(_, expression_type, _)
+            | Expression::Number(_, expression_type, _)
+            | Expression::StringLiteral(_, expression_type, _)
+            | Expression::FunctionCall(_, _, expression_type, _)
+            | Expression::UnaryOp(_, _, expression_type, _)
+            | Expression::Boolean(_, expression_type, _)
+            | Expression::Array(_, expression_type, _)
+            | Expression::ArrayAccess(_, _, expression_type, _) => expression_type.as_ref(),
+        }
+    }
+
+    pub fn set_expression_type(&mut self, new_type: Type) {
+        match self {
+            Expression::BinOp(_, _, _, expression_type, _)
+            | Expression::Identifier(_, expression_type, _)
+            | Expression::Number(_, expression_type, _)
+            | Expression::StringLiteral(_, expression_type, _)
+            | Expression::FunctionCall(_, _, expression_type, _)
+            | Expression::UnaryOp(_, _, expression_type, _)
+            | Expression::Boolean(_, expression_type, _)
+            | Expression::Array(_, expression_type, _)
+            | Expression::ArrayAccess(_, _, expression_type, _) => {
+                *expression_type = Some(new_type)
+            }
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            Expression::BinOp(_, _, _, _, span)
+            | Expression::Identifier(_, _, span)
+            | Expression::Number(_, _, span)
+            | Expression::StringLiteral(_, _, span)
+            | Expression::FunctionCall(_, _, _, span)
+            | Expression::UnaryOp(_, _, _, span)
+            | Expression::Boolean(_, _, span)
+            | Expression::Array(_, _, span)
+            | Expression::ArrayAccess(_, _, _, span) => *span,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -43,14 +123,55 @@ pub enum Op {
     Subtract,
     Multiply,
     Divide,
+    Modulo,
+    Power,
+    BitwiseAnd,
+    BitwiseOr,
+    BitwiseXor,
+    BitwiseNot,
+    ShiftLeft,
+    ShiftRight,
     LessThanEqualTo,
     Equals,
+    NotEquals,
+    And,
+    Or,
+    Not,
     Assign,
     GreaterThanEqualTo,
     GreaterThan,
     LessThan,
 }
 
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Op::Add => write!(f, "+"),
+            Op::Subtract => write!(f, "-"),
+            Op::Multiply => write!(f, "*"),
+            Op::Divide => write!(f, "/"),
+            Op::Modulo => write!(f, "%"),
+            Op::Power => write!(f, "**"),
+            Op::BitwiseAnd => write!(f, "&"),
+            Op::BitwiseOr => write!(f, "|"),
+            Op::BitwiseXor => write!(f, "^"),
+            Op::BitwiseNot => write!(f, "~"),
+            Op::ShiftLeft => write!(f, "<<"),
+            Op::ShiftRight => write!(f, ">>"),
+            Op::LessThanEqualTo => write!(f, "<="),
+            Op::Equals => write!(f, "=="),
+            Op::NotEquals => write!(f, "!="),
+            Op::And => write!(f, "&&"),
+            Op::Or => write!(f, "||"),
+            Op::Not => write!(f, "!"),
+            Op::Assign => write!(f, "="),
+            Op::GreaterThanEqualTo => write!(f, ">="),
+            Op::GreaterThan => write!(f, ">"),
+            Op::LessThan => write!(f, "<"),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum Type {
     Integer,
@@ -62,6 +183,53 @@ pub enum Type {
     Any,
 }
 
+impl Type {
+    // The most precise type that both types describe, or None if they don't fit together. `Any`
+    // stands for an element type that isn't known yet, like that of an empty array, so `[]` and
+    // `[1]` unify to `Array<Integer>`.
+    pub fn unify(&self, other: &Type) -> Option<Type> {
+        match (self, other) {
+            (Type::Any, other) | (other, Type::Any) => Some(other.clone()),
+            (Type::Array(first), Type::Array(second)) => {
+                Some(Type::Array(Box::new(first.unify(second)?)))
+            }
+            _ if self == other => Some(self.clone()),
+            _ => None,
+        }
+    }
+
+    // The type that values of both types can be stored as, e.g. the element type of an array
+    // holding both, or None if there is none. On top of unifying, an Integer and a Decimal are
+    // promoted to a Decimal, so `[1, 2.5]` is an `Array<Decimal>`.
+    pub fn promote(&self, other: &Type) -> Option<Type> {
+        match (self, other) {
+            (Type::Integer, Type::Decimal) | (Type::Decimal, Type::Integer) => Some(Type::Decimal),
+            (Type::Array(first), Type::Array(second)) => {
+                Some(Type::Array(Box::new(first.promote(second)?)))
+            }
+            _ => self.unify(other),
+        }
+    }
+
+    // The type a value of type found takes on when it is stored where self is expected, e.g. in
+    // an annotated variable or a parameter, or None if it can't be stored there. On top of
+    // unifying, an Integer widens to a Decimal, also as the element of an array. A Decimal is
+    // never narrowed to an Integer.
+    pub fn coerce(&self, found: &Type) -> Option<Type> {
+        match (self, found) {
+            (Type::Decimal, Type::Integer) => Some(Type::Decimal),
+            (Type::Array(expected), Type::Array(found)) => {
+                Some(Type::Array(Box::new(expected.coerce(found)?)))
+            }
+            _ => self.unify(found),
+        }
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, Type::Integer | Type::Decimal)
+    }
+}
+
 impl fmt::Display for Type {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
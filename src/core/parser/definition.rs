@@ -1,5 +1,15 @@
+use std::fmt;
+
+use crate::core::diagnostics::Diagnostic;
 use crate::core::parser::ast::Statement;
-use crate::core::token::Token;
+use crate::core::parser::statements_handler::StatementsHandler;
+use crate::core::token::{Span, Token};
+
+// How deeply blocks, and separately parentheses and operators or the brackets of a type, may
+// nest. The parser recurses for each level, so without a limit a long enough run of `(` would
+// overflow the stack. It runs on whatever thread the host calls it from, so this has to fit in a
+// small one.
+pub const MAX_NESTING: usize = 64;
 
 pub struct Parser<'a> {
     pub tokens: &'a [Token],
@@ -10,22 +20,124 @@ pub enum ParseError {
     UnexpectedToken {
         expected: String,
         found: String,
-        line_number: usize,
+        span: Span,
+    },
+    UnexpectedEof {
+        span: Span,
+    },
+    DuplicateParameter {
+        name: String,
+        span: Span,
+    },
+    TooDeeplyNested {
+        span: Span,
     },
 }
 
+impl ParseError {
+    // The handlers only see the tokens from where they started, so they can't tell where the
+    // input ends. Parser::parse fills in the span.
+    pub(crate) fn unexpected_eof() -> ParseError {
+        ParseError::UnexpectedEof {
+            span: Span::default(),
+        }
+    }
+}
+
 impl<'a> Parser<'a> {
     pub fn new(tokens: &'a [Token]) -> Self {
         Parser { tokens }
     }
 
-    pub fn parse(&mut self) -> Result<Vec<Statement>, ParseError> {
-        let mut statement_handler =
-            crate::core::parser::statements_handler::StatementsHandler::new(&self.tokens[0..]);
+    // Fails with every error in the input, not just the first one.
+    pub fn parse(&mut self) -> Result<Vec<Statement>, Vec<ParseError>> {
+        let (statements, errors) = self.parse_partial();
+        if errors.is_empty() {
+            Ok(statements)
+        } else {
+            Err(errors)
+        }
+    }
+
+    // Parses as much of the input as possible. Statements that fail to parse are left out of the
+    // AST and their errors are returned next to it.
+    pub fn parse_partial(&mut self) -> (Vec<Statement>, Vec<ParseError>) {
+        let mut statement_handler = StatementsHandler::new(&self.tokens[0..]);
+        let (statements, _) = statement_handler.handle();
 
-        match statement_handler.handle() {
-            Ok((statements, _)) => Ok(statements),
-            Err(e) => Err(e),
+        let errors = statement_handler
+            .errors()
+            .into_iter()
+            .map(|error| match error {
+                ParseError::UnexpectedEof { .. } => ParseError::UnexpectedEof {
+                    span: self.end_of_input(),
+                },
+                error => error,
+            })
+            .collect();
+        (statements, errors)
+    }
+
+    // The empty span right after the last token.
+    fn end_of_input(&self) -> Span {
+        match self.tokens.last() {
+            Some(token) => Span::new(
+                token.span.end,
+                token.span.end,
+                token.span.line,
+                token.span.column + (token.span.end - token.span.start),
+            ),
+            None => Span::new(0, 0, 1, 1),
+        }
+    }
+}
+
+impl ParseError {
+    pub fn span(&self) -> Span {
+        match self {
+            ParseError::UnexpectedToken { span, .. }
+            | ParseError::UnexpectedEof { span }
+            | ParseError::DuplicateParameter { span, .. }
+            | ParseError::TooDeeplyNested { span } => *span,
+        }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        match self {
+            ParseError::UnexpectedToken {
+                expected,
+                found,
+                span,
+            } => Diagnostic::new(
+                "parse error",
+                format!("expected {} but found {}", expected, found),
+                *span,
+            ),
+            ParseError::UnexpectedEof { span } => {
+                Diagnostic::new("parse error", "unexpected end of input".to_string(), *span)
+            }
+            ParseError::DuplicateParameter { name, span } => Diagnostic::new(
+                "parse error",
+                format!("parameter `{}` is declared more than once", name),
+                *span,
+            ),
+            ParseError::TooDeeplyNested { span } => Diagnostic::new(
+                "parse error",
+                "this is nested too deeply".to_string(),
+                *span,
+            )
+            .with_help(Some(format!(
+                "at most {} blocks, or {} parentheses, brackets and operators, can be nested",
+                MAX_NESTING, MAX_NESTING
+            ))),
         }
     }
 }
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.diagnostic())
+    }
+}
+
+impl std::error::Error for ParseError {}
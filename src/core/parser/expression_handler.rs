@@ -1,11 +1,131 @@
 use crate::core::parser::ast::Expression::Number;
 use crate::core::parser::ast::{Expression, Op};
-use crate::core::parser::definition::ParseError;
-use crate::core::token::Token;
+use crate::core::parser::definition::{ParseError, MAX_NESTING};
+use crate::core::token::{Span, Token, TokenKind};
+
+// Precedence levels, from the loosest to the tightest binding.
+const ASSIGNMENT: u8 = 1;
+const LOGICAL_OR: u8 = 2;
+const LOGICAL_AND: u8 = 3;
+const EQUALITY: u8 = 4;
+const COMPARISON: u8 = 5;
+const BITWISE_OR: u8 = 6;
+const BITWISE_XOR: u8 = 7;
+const BITWISE_AND: u8 = 8;
+const SHIFT: u8 = 9;
+const ADDITIVE: u8 = 10;
+const MULTIPLICATIVE: u8 = 11;
+const UNARY: u8 = 12;
+const POWER: u8 = 13;
+
+enum Associativity {
+    Left,
+    Right,
+}
+
+// Every infix operator with its precedence and associativity. All of them are left associative,
+// so `a - b - c` is `(a - b) - c`, except assignment and power: `a = b = c` is `a = (b = c)` and
+// `a ** b ** c` is `a ** (b ** c)`. Unary `-`, `+`, `!` and `~` bind tighter than any of these
+// but power, so `-a * b` is `(-a) * b` while `-a ** b` is `-(a ** b)`. The bitwise operators bind
+// tighter than comparisons, so `a & 1 == 0` is `(a & 1) == 0`.
+const INFIX_OPERATORS: &[(TokenKind, Op, u8, Associativity)] = &[
+    (
+        TokenKind::Assign,
+        Op::Assign,
+        ASSIGNMENT,
+        Associativity::Right,
+    ),
+    (TokenKind::Or, Op::Or, LOGICAL_OR, Associativity::Left),
+    (TokenKind::And, Op::And, LOGICAL_AND, Associativity::Left),
+    (TokenKind::Eq, Op::Equals, EQUALITY, Associativity::Left),
+    (
+        TokenKind::NotEq,
+        Op::NotEquals,
+        EQUALITY,
+        Associativity::Left,
+    ),
+    (TokenKind::Lt, Op::LessThan, COMPARISON, Associativity::Left),
+    (
+        TokenKind::LtEq,
+        Op::LessThanEqualTo,
+        COMPARISON,
+        Associativity::Left,
+    ),
+    (
+        TokenKind::Gt,
+        Op::GreaterThan,
+        COMPARISON,
+        Associativity::Left,
+    ),
+    (
+        TokenKind::GtEq,
+        Op::GreaterThanEqualTo,
+        COMPARISON,
+        Associativity::Left,
+    ),
+    (
+        TokenKind::Pipe,
+        Op::BitwiseOr,
+        BITWISE_OR,
+        Associativity::Left,
+    ),
+    (
+        TokenKind::Caret,
+        Op::BitwiseXor,
+        BITWISE_XOR,
+        Associativity::Left,
+    ),
+    (
+        TokenKind::Ampersand,
+        Op::BitwiseAnd,
+        BITWISE_AND,
+        Associativity::Left,
+    ),
+    (
+        TokenKind::ShiftLeft,
+        Op::ShiftLeft,
+        SHIFT,
+        Associativity::Left,
+    ),
+    (
+        TokenKind::ShiftRight,
+        Op::ShiftRight,
+        SHIFT,
+        Associativity::Left,
+    ),
+    (TokenKind::Plus, Op::Add, ADDITIVE, Associativity::Left),
+    (
+        TokenKind::Minus,
+        Op::Subtract,
+        ADDITIVE,
+        Associativity::Left,
+    ),
+    (
+        TokenKind::Asterisk,
+        Op::Multiply,
+        MULTIPLICATIVE,
+        Associativity::Left,
+    ),
+    (
+        TokenKind::Slash,
+        Op::Divide,
+        MULTIPLICATIVE,
+        Associativity::Left,
+    ),
+    (
+        TokenKind::Percent,
+        Op::Modulo,
+        MULTIPLICATIVE,
+        Associativity::Left,
+    ),
+    (TokenKind::Power, Op::Power, POWER, Associativity::Right),
+];
 
 pub struct ExpressionHandler<'a> {
     pub start_token: &'a [Token],
     current: usize,
+    // How many calls of parse_expression we are in.
+    depth: usize,
 }
 
 impl<'a> ExpressionHandler<'a> {
@@ -13,12 +133,15 @@ impl<'a> ExpressionHandler<'a> {
         ExpressionHandler {
             start_token: tokens,
             current: 0,
+            depth: 0,
         }
     }
 
     // peek function
-    pub fn peek(&mut self) -> &Token {
-        &self.start_token[self.current]
+    pub fn peek(&self) -> Result<&Token, ParseError> {
+        self.start_token
+            .get(self.current)
+            .ok_or_else(ParseError::unexpected_eof)
     }
 
     //Move ahead to other token
@@ -27,202 +150,200 @@ impl<'a> ExpressionHandler<'a> {
     }
 
     pub fn expression(&mut self) -> Result<(Expression, usize), ParseError> {
-        // handle arrays as well
-        if self.peek().token_type == "LBRACKET" {
-            let mut array = Vec::new();
-            self.move_ahead();
-            while self.peek().token_type != "RBRACKET" {
-                match self.expression() {
-                    Ok((expression, _)) => array.push(expression),
-                    Err(e) => return Err(e),
-                }
-                if self.peek().token_type == "RBRACKET" {
-                    break;
-                }
-                if self.peek().token_type == "COMMA" {
-                    self.move_ahead();
-                } else {
-                    return Err(ParseError::UnexpectedToken {
-                        expected: String::from("COMMA, RBRACKET"),
-                        found: self.peek().token_type.clone(),
-                        line_number: self.peek().line_number,
-                    });
-                }
-            }
-            self.move_ahead();
-            if self.peek().token_type != "SEMICOLON" {
+        let expression = self.parse_expression(ASSIGNMENT)?;
+        Ok((expression, self.current))
+    }
+
+    // Parses an expression that makes up a whole statement. It has to be followed by a `;`, which
+    // is consumed, or by the end of the block or of the input.
+    pub fn statement(&mut self) -> Result<(Expression, usize), ParseError> {
+        let expression = self.parse_expression(ASSIGNMENT)?;
+        match self.start_token.get(self.current) {
+            Some(token) if token.kind == TokenKind::Semicolon => self.move_ahead(),
+            Some(token) if token.kind != TokenKind::RBrace => {
                 return Err(ParseError::UnexpectedToken {
-                    expected: String::from("SEMICOLON"),
-                    found: self.peek().token_type.clone(),
-                    line_number: self.peek().line_number,
+                    expected: TokenKind::Semicolon.to_string(),
+                    found: token.kind.to_string(),
+                    span: token.span,
                 });
             }
-            self.move_ahead();
-            return Ok((Expression::Array(array, None), self.current));
-        }
-
-        // Handle term
-        let mut left = self.handle_term()?;
-        if self.peek().token_type == "ASSIGN"
-            || self.peek().token_type == "LT_EQ"
-            || self.peek().token_type == "EQ"
-            || self.peek().token_type == "GT_EQ"
-            || self.peek().token_type == "GT"
-            || self.peek().token_type == "LT"
-        {
-            let operation = match self.peek().token_type.clone().as_str() {
-                "ASSIGN" => Op::Assign,
-                "LT_EQ" => Op::LessThanEqualTo,
-                "EQ" => Op::Equals,
-                "GT_EQ" => Op::GreaterThanEqualTo,
-                "GT" => Op::GreaterThan,
-                "LT" => Op::LessThan,
-                _ => Op::Assign,
-            };
-
-            self.move_ahead();
-            let mut expression_handler = ExpressionHandler::new(&self.start_token[self.current..]);
-            match expression_handler.expression() {
-                Ok((right, current)) => {
-                    self.current += current;
-                    left = Expression::BinOp(Box::new(left), operation, Box::new(right), None);
-                }
-                Err(e) => return Err(e),
-            }
-            return Ok((left, self.current));
+            _ => {}
         }
+        Ok((expression, self.current))
+    }
 
-        while self.peek().token_type == "PLUS" || self.peek().token_type == "MINUS" {
-            let operation = self.peek().token_type.clone();
-            self.move_ahead();
-            let right = self.handle_term()?;
-            let mut op = Op::Add;
-            if operation == "MINUS" {
-                op = Op::Subtract;
-            }
-            left = Expression::BinOp(Box::new(left), op, Box::new(right), None);
-        }
-        if self.peek().token_type == "SEMICOLON" {
-            self.move_ahead();
+    // Every nested expression, from parentheses, unary operators, arrays, arguments or the right
+    // side of an operator, goes through here, so this is where the nesting is limited.
+    fn parse_expression(&mut self, min_precedence: u8) -> Result<Expression, ParseError> {
+        if self.depth == MAX_NESTING {
+            return Err(ParseError::TooDeeplyNested {
+                span: self.peek()?.span,
+            });
         }
-        Ok((left, self.current))
+        self.depth += 1;
+        let expression = self.climb(min_precedence);
+        self.depth -= 1;
+        expression
     }
 
-    fn handle_term(&mut self) -> Result<Expression, ParseError> {
+    // Precedence climbing: parses a prefix expression, then keeps folding in infix operators
+    // that bind at least as tightly as min_precedence.
+    fn climb(&mut self, min_precedence: u8) -> Result<Expression, ParseError> {
         let mut left = self.handle_factor()?;
-        while self.peek().token_type == "ASTERISK" || self.peek().token_type == "SLASH" {
-            let operation = self.peek().token_type.clone();
-            self.move_ahead();
-            let right = self.handle_factor()?;
-            let mut op = Op::Multiply;
-            if operation == "SLASH" {
-                op = Op::Divide;
+        while let Some((_, operation, precedence, associativity)) =
+            self.start_token.get(self.current).and_then(|token| {
+                INFIX_OPERATORS
+                    .iter()
+                    .find(|(kind, ..)| *kind == token.kind)
+            })
+        {
+            if *precedence < min_precedence {
+                break;
             }
-            left = Expression::BinOp(Box::new(left), op, Box::new(right), None);
-        }
+            self.move_ahead();
 
-        // check if LParen, number or identifier
-        while self.peek().token_type == "LPAREN" {
-            let right = self.handle_factor()?;
-            left = Expression::BinOp(Box::new(left), Op::Multiply, Box::new(right), None);
+            // The right operand of a left associative operator may only contain operators that
+            // bind tighter, so that the next operator of the same level ends up on top.
+            let right = match associativity {
+                Associativity::Left => self.parse_expression(precedence + 1)?,
+                Associativity::Right => self.parse_expression(*precedence)?,
+            };
+            let span = left.span().to(right.span());
+            left = Expression::BinOp(
+                Box::new(left),
+                operation.clone(),
+                Box::new(right),
+                None,
+                span,
+            );
         }
-
         Ok(left)
     }
 
+    // This is on the stack once for every level of nesting, so arrays and calls are parsed in
+    // functions of their own to keep its frame small.
     fn handle_factor(&mut self) -> Result<Expression, ParseError> {
-        let mut left_token_type = self.peek().token_type.clone();
+        let span = self.peek()?.span;
         //  Handle Number, parenthesis, prefix expression
-        if left_token_type == *"NUMBER" {
-            let n = Number(self.peek().literal.clone(), None);
-            self.move_ahead();
-            return Ok(n);
-        }
-
-        if left_token_type == *"MINUS" {
-            self.move_ahead();
-            let expr = self.handle_factor()?;
-            return Ok(Expression::UnaryOp(Op::Subtract, Box::new(expr), None));
-        }
-
-        if left_token_type == *"PLUS" {
-            self.move_ahead();
-            let expr = self.handle_factor()?;
-            return Ok(Expression::UnaryOp(Op::Add, Box::new(expr), None));
-        }
-
-        if left_token_type == *"LPAREN" {
-            self.move_ahead();
-            let (expression, _) = self.expression()?;
-            left_token_type = self.peek().token_type.clone();
-            return if left_token_type == *"RPAREN" {
+        match self.peek()?.kind.clone() {
+            TokenKind::LBracket => {
+                self.move_ahead();
+                self.array(span)
+            }
+            TokenKind::Number(number) => {
+                self.move_ahead();
+                Ok(Number(number, None, span))
+            }
+            TokenKind::Minus | TokenKind::Bang | TokenKind::Plus | TokenKind::Tilde => {
+                let operator = match self.peek()?.kind {
+                    TokenKind::Minus => Op::Subtract,
+                    TokenKind::Bang => Op::Not,
+                    TokenKind::Plus => Op::Add,
+                    _ => Op::BitwiseNot,
+                };
+                self.move_ahead();
+                let expr = self.parse_expression(UNARY)?;
+                let span = span.to(expr.span());
+                Ok(Expression::UnaryOp(operator, Box::new(expr), None, span))
+            }
+            TokenKind::LParen => {
                 self.move_ahead();
+                let (expression, _) = self.expression()?;
+                self.expect(TokenKind::RParen)?;
                 Ok(expression)
-            } else {
-                // We need to see if there are any other signs...
-                Err(ParseError::UnexpectedToken {
-                    expected: String::from("RPAREN"),
-                    found: left_token_type,
-                    line_number: self.peek().line_number,
-                })
-            };
-        }
-
-        // handle string
-        if left_token_type == *"STRING" {
-            let s = self.peek().literal.clone();
-            self.move_ahead();
-            return Ok(Expression::StringLiteral(s, None));
-        }
-
-        // Handle identifier and function calls
-        if left_token_type == *"IDENTIFIER" {
-            let s = self.peek().literal.clone();
-            self.move_ahead();
-            if self.peek().token_type == "LPAREN" {
+            }
+            // handle string
+            TokenKind::String(s) => {
                 self.move_ahead();
-                let mut args = Vec::new();
-                while self.peek().token_type != "RPAREN" {
-                    let (expression, _) = self.expression()?;
-                    args.push(expression);
-                    if self.peek().token_type == "COMMA" {
+                Ok(Expression::StringLiteral(s, None, span))
+            }
+            // Handle identifier and function calls
+            TokenKind::Identifier(s) => {
+                self.move_ahead();
+                match self.peek()?.kind {
+                    TokenKind::LParen => {
+                        self.move_ahead();
+                        self.call(s, span)
+                    }
+                    TokenKind::LBracket => {
                         self.move_ahead();
+                        let (expression, _) = self.expression()?;
+                        let span = span.to(self.peek()?.span);
+                        self.expect(TokenKind::RBracket)?;
+                        Ok(Expression::ArrayAccess(s, Box::new(expression), None, span))
                     }
+                    _ => Ok(Expression::Identifier(s, None, span)),
                 }
-                self.move_ahead();
-                return Ok(Expression::FunctionCall(s, args, None));
             }
-            if self.peek().token_type == "LBRACKET" {
+            TokenKind::True => {
                 self.move_ahead();
-                let (expression, _) = self.expression()?;
-                if self.peek().token_type != "RBRACKET" {
-                    return Err(ParseError::UnexpectedToken {
-                        expected: String::from("RBRACKET"),
-                        found: self.peek().token_type.clone(),
-                        line_number: self.peek().line_number,
-                    });
-                }
+                Ok(Expression::Boolean(true, None, span))
+            }
+            TokenKind::False => {
                 self.move_ahead();
-                return Ok(Expression::ArrayAccess(s, Box::new(expression), None));
+                Ok(Expression::Boolean(false, None, span))
             }
+            kind => Err(ParseError::UnexpectedToken {
+                expected: String::from("an expression"),
+                found: kind.to_string(),
+                span,
+            }),
+        }
+    }
 
-            return Ok(Expression::Identifier(s, None));
+    // The elements of an array literal, after its `[`.
+    fn array(&mut self, span: Span) -> Result<Expression, ParseError> {
+        let mut array = Vec::new();
+        while self.peek()?.kind != TokenKind::RBracket {
+            array.push(self.parse_expression(ASSIGNMENT)?);
+            if self.peek()?.kind == TokenKind::RBracket {
+                break;
+            }
+            self.separator(TokenKind::RBracket)?;
         }
+        let span = span.to(self.peek()?.span);
+        self.move_ahead();
+        Ok(Expression::Array(array, None, span))
+    }
 
-        if left_token_type == *"TRUE" {
-            self.move_ahead();
-            return Ok(Expression::Boolean(true, None));
+    // The arguments of a call, after its `(`.
+    fn call(&mut self, name: String, span: Span) -> Result<Expression, ParseError> {
+        let mut args = Vec::new();
+        while self.peek()?.kind != TokenKind::RParen {
+            let (expression, _) = self.expression()?;
+            args.push(expression);
+            if self.peek()?.kind != TokenKind::RParen {
+                self.separator(TokenKind::RParen)?;
+            }
         }
+        let span = span.to(self.peek()?.span);
+        self.move_ahead();
+        Ok(Expression::FunctionCall(name, args, None, span))
+    }
 
-        if left_token_type == *"FALSE" {
+    // Skips the `,` between two elements or arguments, which must otherwise be followed by the
+    // closing token.
+    fn separator(&mut self, closing: TokenKind) -> Result<(), ParseError> {
+        if self.peek()?.kind == TokenKind::Comma {
             self.move_ahead();
-            return Ok(Expression::Boolean(false, None));
+            return Ok(());
         }
-
         Err(ParseError::UnexpectedToken {
-            expected: String::from("NUMBER, LPAREN"),
-            found: left_token_type,
-            line_number: self.peek().line_number,
+            expected: format!("{} or {}", TokenKind::Comma, closing),
+            found: self.peek()?.kind.to_string(),
+            span: self.peek()?.span,
         })
     }
+
+    fn expect(&mut self, kind: TokenKind) -> Result<(), ParseError> {
+        if self.peek()?.kind != kind {
+            return Err(ParseError::UnexpectedToken {
+                expected: kind.to_string(),
+                found: self.peek()?.kind.to_string(),
+                span: self.peek()?.span,
+            });
+        }
+        self.move_ahead();
+        Ok(())
+    }
 }
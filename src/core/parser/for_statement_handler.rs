@@ -1,7 +1,8 @@
 use crate::core::parser::ast::Statement;
 use crate::core::parser::definition::ParseError;
 use crate::core::parser::expression_handler::ExpressionHandler;
-use crate::core::token::Token;
+use crate::core::parser::statements_handler::StatementsHandler;
+use crate::core::token::{Token, TokenKind};
 
 pub struct ForStatementHandler<'a> {
     pub start_token: &'a [Token],
@@ -16,15 +17,21 @@ impl<'a> ForStatementHandler<'a> {
         }
     }
 
-    pub fn parse(mut self) -> Result<(Statement, usize), ParseError> {
-        let mut token_type = self.start_token[self.current].token_type.clone();
+    pub fn parse(
+        mut self,
+        parent: &mut StatementsHandler,
+    ) -> Result<(Statement, usize), ParseError> {
+        let mut token = self
+            .start_token
+            .get(self.current)
+            .ok_or_else(ParseError::unexpected_eof)?;
 
         // the token type should be open parenthesis
-        if token_type != "LPAREN" {
+        if token.kind != TokenKind::LParen {
             return Err(ParseError::UnexpectedToken {
-                expected: "(".to_string(),
-                found: token_type.clone().to_string(),
-                line_number: self.start_token[self.current].line_number,
+                expected: TokenKind::LParen.to_string(),
+                found: token.kind.to_string(),
+                span: token.span,
             });
         }
 
@@ -41,6 +48,20 @@ impl<'a> ForStatementHandler<'a> {
             Err(e) => return Err(e),
         };
 
+        // the initialization and the condition both end with a semicolon
+        token = self
+            .start_token
+            .get(self.current)
+            .ok_or_else(ParseError::unexpected_eof)?;
+        if token.kind != TokenKind::Semicolon {
+            return Err(ParseError::UnexpectedToken {
+                expected: TokenKind::Semicolon.to_string(),
+                found: token.kind.to_string(),
+                span: token.span,
+            });
+        }
+        self.current += 1;
+
         // parse the second expression
         let mut expn_handler = ExpressionHandler::new(&self.start_token[self.current..]);
         let second_expression = match expn_handler.expression() {
@@ -51,6 +72,20 @@ impl<'a> ForStatementHandler<'a> {
             Err(e) => return Err(e),
         };
 
+        // the initialization and the condition both end with a semicolon
+        token = self
+            .start_token
+            .get(self.current)
+            .ok_or_else(ParseError::unexpected_eof)?;
+        if token.kind != TokenKind::Semicolon {
+            return Err(ParseError::UnexpectedToken {
+                expected: TokenKind::Semicolon.to_string(),
+                found: token.kind.to_string(),
+                span: token.span,
+            });
+        }
+        self.current += 1;
+
         // parse the third expression
         let mut expn_handler = ExpressionHandler::new(&self.start_token[self.current..]);
         let third_expression = match expn_handler.expression() {
@@ -62,64 +97,36 @@ impl<'a> ForStatementHandler<'a> {
         };
 
         // the token type should be close parenthesis
-        token_type = self.start_token[self.current].token_type.clone();
-        if token_type != "RPAREN" {
-            return Err(ParseError::UnexpectedToken {
-                expected: ")".to_string(),
-                found: token_type.clone().to_string(),
-                line_number: self.start_token[self.current].line_number,
-            });
-        }
-
-        // increment the current
-        self.current += 1;
-
-        // The token type should be open brace
-        token_type = self.start_token[self.current].token_type.clone();
-        if token_type != "LBRACE" {
+        token = self
+            .start_token
+            .get(self.current)
+            .ok_or_else(ParseError::unexpected_eof)?;
+        if token.kind != TokenKind::RParen {
             return Err(ParseError::UnexpectedToken {
-                expected: "{".to_string(),
-                found: token_type.clone().to_string(),
-                line_number: self.start_token[self.current].line_number,
+                expected: TokenKind::RParen.to_string(),
+                found: token.kind.to_string(),
+                span: token.span,
             });
         }
 
         // increment the current
         self.current += 1;
 
-        let mut parser = crate::core::parser::statements_handler::StatementsHandler::new(
-            &self.start_token[self.current..],
-        );
-
-        parser.set_blocked();
-        let statements = parser.handle();
-
-        let statements = match statements {
-            Ok((statements, consumed)) => {
-                self.current += consumed;
-                statements
-            }
-            Err(e) => return Err(e),
-        };
-
-        // the token type should be close brace
-        token_type = self.start_token[self.current].token_type.clone();
-        if token_type != "RBRACE" {
-            return Err(ParseError::UnexpectedToken {
-                expected: "}".to_string(),
-                found: token_type.clone().to_string(),
-                line_number: self.start_token[self.current].line_number,
-            });
-        }
+        let (statements, consumed) = parent.block(&self.start_token[self.current..])?;
+        self.current += consumed;
 
+        let span = self.start_token[0]
+            .span
+            .to(self.start_token[self.current - 1].span);
         Ok((
             Statement::ForStatement(
                 Box::new(first_expression),
                 Box::new(second_expression),
                 Box::new(third_expression),
                 statements,
+                span,
             ),
-            self.current + 1,
+            self.current,
         ))
     }
 }
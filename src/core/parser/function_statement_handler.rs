@@ -0,0 +1,118 @@
+use crate::core::parser::ast::{Parameter, Statement};
+use crate::core::parser::definition::ParseError;
+use crate::core::parser::statements_handler::StatementsHandler;
+use crate::core::parser::type_handler::TypeHandler;
+use crate::core::token::{Token, TokenKind};
+
+pub struct FunctionStatementHandler<'a> {
+    pub start_token: &'a [Token],
+    current: usize,
+}
+
+impl<'a> FunctionStatementHandler<'a> {
+    pub fn new(tokens: &'a [Token]) -> Self {
+        FunctionStatementHandler {
+            start_token: tokens,
+            current: 1,
+        }
+    }
+
+    // peek function
+    pub fn peek(&self) -> Result<&Token, ParseError> {
+        self.start_token
+            .get(self.current)
+            .ok_or_else(ParseError::unexpected_eof)
+    }
+
+    //Move ahead to other token
+    pub fn move_ahead(&mut self) {
+        self.current += 1;
+    }
+
+    // function name(a: int, b: string): int { ... }
+    pub fn parse(
+        mut self,
+        parent: &mut StatementsHandler,
+    ) -> Result<(Statement, usize), ParseError> {
+        let name = self.identifier()?;
+
+        self.expect(TokenKind::LParen)?;
+        let mut parameters = Vec::new();
+        while self.peek()?.kind != TokenKind::RParen {
+            let parameter_span = self.peek()?.span;
+            let parameter_name = self.identifier()?;
+            if parameters
+                .iter()
+                .any(|Parameter::Identifier(name, _)| *name == parameter_name)
+            {
+                return Err(ParseError::DuplicateParameter {
+                    name: parameter_name,
+                    span: parameter_span,
+                });
+            }
+            self.expect(TokenKind::Colon)?;
+            let (parameter_type, consumed) =
+                TypeHandler::new(&self.start_token[self.current..]).parse()?;
+            self.current += consumed;
+            parameters.push(Parameter::Identifier(parameter_name, parameter_type));
+
+            if self.peek()?.kind == TokenKind::Comma {
+                self.move_ahead();
+            } else if self.peek()?.kind != TokenKind::RParen {
+                return Err(ParseError::UnexpectedToken {
+                    expected: format!("{} or {}", TokenKind::Comma, TokenKind::RParen),
+                    found: self.peek()?.kind.to_string(),
+                    span: self.peek()?.span,
+                });
+            }
+        }
+        self.move_ahead();
+
+        // Functions without a return type don't return anything.
+        let mut return_type = None;
+        if self.peek()?.kind == TokenKind::Colon {
+            self.move_ahead();
+            let (parsed_type, consumed) =
+                TypeHandler::new(&self.start_token[self.current..]).parse()?;
+            self.current += consumed;
+            return_type = Some(parsed_type);
+        }
+
+        let (statements, consumed) = parent.block(&self.start_token[self.current..])?;
+        self.current += consumed;
+
+        let span = self.start_token[0]
+            .span
+            .to(self.start_token[self.current - 1].span);
+        Ok((
+            Statement::FunctionDeclaration(name, parameters, statements, return_type, span),
+            self.current,
+        ))
+    }
+
+    fn identifier(&mut self) -> Result<String, ParseError> {
+        match self.peek()?.kind.clone() {
+            TokenKind::Identifier(name) => {
+                self.move_ahead();
+                Ok(name)
+            }
+            kind => Err(ParseError::UnexpectedToken {
+                expected: "an identifier".to_string(),
+                found: kind.to_string(),
+                span: self.peek()?.span,
+            }),
+        }
+    }
+
+    fn expect(&mut self, kind: TokenKind) -> Result<(), ParseError> {
+        if self.peek()?.kind != kind {
+            return Err(ParseError::UnexpectedToken {
+                expected: kind.to_string(),
+                found: self.peek()?.kind.to_string(),
+                span: self.peek()?.span,
+            });
+        }
+        self.move_ahead();
+        Ok(())
+    }
+}
@@ -1,7 +1,8 @@
 use crate::core::parser::ast::Statement;
 use crate::core::parser::definition::ParseError;
 use crate::core::parser::expression_handler::ExpressionHandler;
-use crate::core::token::Token;
+use crate::core::parser::statements_handler::StatementsHandler;
+use crate::core::token::{Token, TokenKind};
 
 pub struct IfStatementHandler<'a> {
     pub start_token: &'a [Token],
@@ -17,8 +18,10 @@ impl<'a> IfStatementHandler<'a> {
     }
 
     // peek function
-    pub fn peek(&mut self) -> &Token {
-        &self.start_token[self.current]
+    pub fn peek(&self) -> Result<&Token, ParseError> {
+        self.start_token
+            .get(self.current)
+            .ok_or_else(ParseError::unexpected_eof)
     }
 
     //Move ahead to other token
@@ -26,7 +29,10 @@ impl<'a> IfStatementHandler<'a> {
         self.current += 1;
     }
 
-    pub fn parse(mut self) -> Result<(Statement, usize), ParseError> {
+    pub fn parse(
+        mut self,
+        parent: &mut StatementsHandler,
+    ) -> Result<(Statement, usize), ParseError> {
         // Because the default value for current = 1, we can directly start processing the
         // expression.
         //
@@ -35,52 +41,41 @@ impl<'a> IfStatementHandler<'a> {
         let (expression, cursor) = expression_parser.expression()?;
         self.current += cursor;
 
-        // The next token must be a left parenthesis
-        if self.peek().token_type == "LBRACE" {
-            self.move_ahead();
-        } else {
-            return Err(ParseError::UnexpectedToken {
-                expected: "{".to_string(),
-                found: self.peek().token_type.clone(),
-                line_number: self.peek().line_number,
-            });
-        }
-
-        // Now we are inside the curly brances, so we need to start handling other statements.
-        // Let's delegate that to the StatementHandler
-        let mut parser = crate::core::parser::statements_handler::StatementsHandler::new(
-            &self.start_token[self.current..],
-        );
+        let statements = self.block(parent)?;
 
-        parser.set_blocked();
-        let statements = parser.handle();
-
-        let statements = match statements {
-            Ok((statements, consumed)) => {
+        // An else branch is optional. `else if` is just an else branch holding another if
+        // statement, so chains of them nest.
+        let mut else_statements = None;
+        if self
+            .start_token
+            .get(self.current)
+            .is_some_and(|token| token.kind == TokenKind::Else)
+        {
+            self.move_ahead();
+            if self.peek()?.kind == TokenKind::If {
+                let (statement, consumed) =
+                    IfStatementHandler::new(&self.start_token[self.current..]).parse(parent)?;
                 self.current += consumed;
-                statements
+                else_statements = Some(vec![statement]);
+            } else {
+                else_statements = Some(self.block(parent)?);
             }
-            Err(e) => return Err(e),
-        };
-
-        //self.current += cursor; // Not sure if we need this.
-
-        // Now that we have list of statements, we need to check if the right curly brace has been
-        // closed successfully.
-        if self.peek().token_type == "RBRACE" {
-            self.move_ahead();
-        } else {
-            return Err(ParseError::UnexpectedToken {
-                expected: "}".to_string(),
-                found: self.peek().token_type.clone(),
-                line_number: self.peek().line_number,
-            });
         }
 
         // All good, read to return the IfStatement with expression and enclosed statements
+        let span = self.start_token[0]
+            .span
+            .to(self.start_token[self.current - 1].span);
         Ok((
-            Statement::IfStatement(Box::from(expression), statements),
+            Statement::IfStatement(Box::from(expression), statements, else_statements, span),
             self.current,
         ))
     }
+
+    // Parses `{ statements }` and returns the enclosed statements.
+    fn block(&mut self, parent: &mut StatementsHandler) -> Result<Vec<Statement>, ParseError> {
+        let (statements, consumed) = parent.block(&self.start_token[self.current..])?;
+        self.current += consumed;
+        Ok(statements)
+    }
 }
@@ -1,7 +1,8 @@
 use crate::core::parser::ast::Statement;
 use crate::core::parser::definition::ParseError;
 use crate::core::parser::expression_handler::ExpressionHandler;
-use crate::core::token::Token;
+use crate::core::parser::type_handler::TypeHandler;
+use crate::core::token::{Token, TokenKind};
 
 pub struct LetStatementHandler<'a> {
     pub start_token: &'a [Token],
@@ -17,40 +18,62 @@ impl<'a> LetStatementHandler<'a> {
     }
 
     pub fn parse(mut self) -> Result<(Statement, usize), ParseError> {
-        let mut token_type = self.start_token[self.current].token_type.clone();
-        let identifier;
-        if token_type == "IDENTIFIER" {
-            identifier = self.start_token[self.current].literal.clone();
+        let token = self
+            .start_token
+            .get(self.current)
+            .ok_or_else(ParseError::unexpected_eof)?;
+        let identifier = match &token.kind {
+            TokenKind::Identifier(identifier) => identifier.clone(),
+            kind => {
+                return Err(ParseError::UnexpectedToken {
+                    expected: "an identifier".to_string(),
+                    found: kind.to_string(),
+                    span: token.span,
+                });
+            }
+        };
+        self.current += 1;
+
+        // An optional annotation, as in `let count: int = 0;`.
+        let mut annotation = None;
+        let token = self
+            .start_token
+            .get(self.current)
+            .ok_or_else(ParseError::unexpected_eof)?;
+        if token.kind == TokenKind::Colon {
             self.current += 1;
-        } else {
-            return Err(ParseError::UnexpectedToken {
-                expected: "IDENTIFIER".to_string(),
-                found: token_type,
-                line_number: self.start_token[self.current].line_number,
-            });
+            let (annotated_type, consumed) =
+                TypeHandler::new(&self.start_token[self.current..]).parse()?;
+            annotation = Some(annotated_type);
+            self.current += consumed;
         }
 
-        token_type = self.start_token[self.current].token_type.clone();
-
-        if token_type == "ASSIGN" {
+        let token = self
+            .start_token
+            .get(self.current)
+            .ok_or_else(ParseError::unexpected_eof)?;
+        if token.kind == TokenKind::Assign {
             self.current += 1;
         } else {
             return Err(ParseError::UnexpectedToken {
-                expected: "ASSIGN".to_string(),
-                found: token_type,
-                line_number: self.start_token[self.current].line_number,
+                expected: TokenKind::Assign.to_string(),
+                found: token.kind.to_string(),
+                span: token.span,
             });
         }
 
         // We expect an expression here, if its not expression then throw error
         let mut expression_parser = ExpressionHandler::new(&self.start_token[self.current..]);
-        let expression = expression_parser.expression();
+        let expression = expression_parser.statement();
 
         match expression {
             Ok((expression, consumed)) => {
                 self.current += consumed;
+                let span = self.start_token[0]
+                    .span
+                    .to(self.start_token[self.current - 1].span);
                 Ok((
-                    Statement::Let(identifier, Box::from(expression)),
+                    Statement::Let(identifier, annotation, Box::from(expression), span),
                     self.current,
                 ))
             }
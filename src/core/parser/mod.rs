@@ -2,8 +2,11 @@ pub mod ast;
 pub mod definition;
 mod expression_handler;
 mod for_statement_handler;
+mod function_statement_handler;
 mod if_statement_handler;
 mod let_statement_handler;
 mod statements_handler;
+mod type_handler;
+mod while_statement_handler;
 
 pub use definition::Parser;
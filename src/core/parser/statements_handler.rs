@@ -1,14 +1,21 @@
 use crate::core::parser::ast::Statement;
-use crate::core::parser::definition::ParseError;
+use crate::core::parser::definition::{ParseError, MAX_NESTING};
 use crate::core::parser::expression_handler::ExpressionHandler;
+use crate::core::parser::for_statement_handler::ForStatementHandler;
+use crate::core::parser::function_statement_handler::FunctionStatementHandler;
 use crate::core::parser::if_statement_handler::IfStatementHandler;
 use crate::core::parser::let_statement_handler::LetStatementHandler;
-use crate::core::token::Token;
+use crate::core::parser::while_statement_handler::WhileStatementHandler;
+use crate::core::token::{Span, Token, TokenKind};
 
 pub struct StatementsHandler<'a> {
     pub tokens: &'a [Token],
     current: usize,
     is_inside_brances: bool,
+    // How many blocks we are nested in.
+    depth: usize,
+    // Every error found so far, including the ones in nested blocks.
+    errors: Vec<ParseError>,
 }
 
 impl<'a> StatementsHandler<'a> {
@@ -17,6 +24,8 @@ impl<'a> StatementsHandler<'a> {
             tokens,
             current: 0,
             is_inside_brances: false,
+            depth: 0,
+            errors: Vec::new(),
         }
     }
 
@@ -24,122 +33,207 @@ impl<'a> StatementsHandler<'a> {
         self.is_inside_brances = true;
     }
 
-    pub fn handle(&mut self) -> Result<(Vec<Statement>, usize), ParseError> {
+    pub fn errors(self) -> Vec<ParseError> {
+        self.errors
+    }
+
+    // Parses statements until the end of the input, or the closing brace of the block. A
+    // statement that fails to parse is recorded in the errors and skipped, so that one typo
+    // doesn't hide the errors after it.
+    pub fn handle(&mut self) -> (Vec<Statement>, usize) {
         let mut nodes = Vec::new();
 
-        while self.current < self.tokens.len() {
-            // Check if the current token indicates a 'let' statement
-            if let Some(token) = self.tokens.get(self.current) {
-                if token.token_type == "LET" {
-                    let handler = LetStatementHandler::new(&self.tokens[self.current..]);
-                    match handler.parse() {
-                        Ok((node, consumed)) => {
-                            nodes.push(node);
-                            self.current += consumed;
-                        }
-                        Err(e) => return Err(e),
-                    }
+        while let Some(token) = self.tokens.get(self.current) {
+            if token.kind == TokenKind::RBrace {
+                if self.is_inside_brances {
+                    break;
                 }
-                if token.token_type == "IF" {
-                    let handler = IfStatementHandler::new(&self.tokens[self.current..]);
-                    match handler.parse() {
-                        Ok((node, consumed)) => {
-                            nodes.push(node);
-                            self.current += consumed;
-                        }
-                        Err(e) => return Err(e),
-                    }
+                self.errors.push(ParseError::UnexpectedToken {
+                    expected: "a statement".to_string(),
+                    found: token.kind.to_string(),
+                    span: token.span,
+                });
+                self.current += 1;
+                continue;
+            }
+
+            match self.statement(token) {
+                Ok((node, consumed)) => {
+                    nodes.push(node);
+                    self.current += consumed;
                 }
+                Err(e) => self.recover(e),
+            }
+        }
+
+        (nodes, self.current)
+    }
+
+    // Parses `{ statements }` at the start of `tokens`, for the bodies of if, for, while and
+    // functions. Returns the statements and the number of tokens consumed, braces included. The
+    // errors inside the block are kept with ours.
+    pub fn block(&mut self, tokens: &[Token]) -> Result<(Vec<Statement>, usize), ParseError> {
+        let open = tokens.first().ok_or_else(ParseError::unexpected_eof)?;
+        if open.kind != TokenKind::LBrace {
+            return Err(ParseError::UnexpectedToken {
+                expected: TokenKind::LBrace.to_string(),
+                found: open.kind.to_string(),
+                span: open.span,
+            });
+        }
+
+        if self.depth == MAX_NESTING {
+            return Err(ParseError::TooDeeplyNested { span: open.span });
+        }
+
+        let mut parser = StatementsHandler::new(&tokens[1..]);
+        parser.set_blocked();
+        parser.depth = self.depth + 1;
+        let (statements, consumed) = parser.handle();
+        self.errors.append(&mut parser.errors);
+
+        let close = tokens
+            .get(consumed + 1)
+            .ok_or_else(ParseError::unexpected_eof)?;
+        if close.kind != TokenKind::RBrace {
+            return Err(ParseError::UnexpectedToken {
+                expected: TokenKind::RBrace.to_string(),
+                found: close.kind.to_string(),
+                span: close.span,
+            });
+        }
+        Ok((statements, consumed + 2))
+    }
 
-                if token.token_type == "FOR" {
-                    let handler =
-                        crate::core::parser::for_statement_handler::ForStatementHandler::new(
-                            &self.tokens[self.current..],
-                        );
-                    match handler.parse() {
-                        Ok((node, consumed)) => {
-                            nodes.push(node);
-                            self.current += consumed;
-                        }
-                        Err(e) => return Err(e),
+    fn statement(&mut self, token: &Token) -> Result<(Statement, usize), ParseError> {
+        let tokens = &self.tokens[self.current..];
+        match &token.kind {
+            TokenKind::Let => LetStatementHandler::new(tokens).parse(),
+            TokenKind::If => IfStatementHandler::new(tokens).parse(self),
+            TokenKind::For => ForStatementHandler::new(tokens).parse(self),
+            TokenKind::While => WhileStatementHandler::new(tokens).parse(self),
+            TokenKind::Function => FunctionStatementHandler::new(tokens).parse(self),
+
+            // `return;` or `return expression;`
+            TokenKind::Return => {
+                if let Some(next_token) = tokens.get(1) {
+                    if next_token.kind == TokenKind::Semicolon {
+                        return Ok((Statement::Return(None, token.span.to(next_token.span)), 2));
                     }
                 }
+                let mut handler = ExpressionHandler::new(&tokens[1..]);
+                let (expr, consumed) = handler.statement()?;
+                Ok((
+                    Statement::Return(Some(Box::new(expr)), self.span_of(consumed + 1)),
+                    consumed + 1,
+                ))
+            }
 
-                // Need to handle identifier.
-                if token.token_type == "IDENTIFIER" {
-                    // first we need to be sure that the next token is an assignment operator.
-                    if let Some(next_token) = self.tokens.get(self.current + 1) {
-                        if next_token.token_type == "ASSIGN" {
-                            let mut handler = ExpressionHandler::new(&self.tokens[self.current..]);
-                            match handler.expression() {
-                                Ok((expr, consumed)) => {
-                                    // Make sure a Statement node is pushed.
-                                    nodes.push(Statement::Assignment(
-                                        token.literal.clone(),
-                                        Box::new(expr),
-                                    ));
-                                    self.current += consumed;
-                                }
-                                Err(e) => return Err(e),
-                            }
-                        }
-                    } else {
+            // `break;` and `continue;`. Whether they are inside of a loop is up to the
+            // analyzer.
+            TokenKind::Break | TokenKind::Continue => {
+                match tokens.get(1) {
+                    Some(next_token) if next_token.kind == TokenKind::Semicolon => {}
+                    Some(next_token) => {
                         return Err(ParseError::UnexpectedToken {
-                            expected: "ASSIGN".to_string(),
-                            found: "EOF".to_string(),
-                            line_number: token.line_number,
-                        });
+                            expected: TokenKind::Semicolon.to_string(),
+                            found: next_token.kind.to_string(),
+                            span: next_token.span,
+                        })
                     }
+                    None => return Err(ParseError::unexpected_eof()),
+                }
+                let node = if token.kind == TokenKind::Break {
+                    Statement::Break(token.span)
+                } else {
+                    Statement::Continue(token.span)
+                };
+                Ok((node, 2))
+            }
 
-                    if let Some(next_token) = self.tokens.get(self.current + 1) {
-                        if next_token.token_type == "LBRACKET" {
-                            // There is a good possibility that this is an array assignment. Good
-                            // possibility, that does not make sense at all. It does have
-                            // possibility though.
-                            let mut handler = ExpressionHandler::new(&self.tokens[self.current..]);
-                            match handler.expression() {
-                                Ok((expr, consumed)) => {
-                                    self.current += consumed;
-                                    // Its at most binary operation, so just push it.
-                                    nodes.push(Statement::ExpressionStatement(Box::new(expr)));
-                                }
-                                Err(e) => return Err(e),
-                            }
-
-                        }
-                    }
+            // `x = expression;`
+            TokenKind::Identifier(identifier)
+                if tokens
+                    .get(1)
+                    .is_some_and(|next_token| next_token.kind == TokenKind::Assign) =>
+            {
+                let mut handler = ExpressionHandler::new(tokens);
+                let (expr, consumed) = handler.statement()?;
+                Ok((
+                    Statement::Assignment(
+                        identifier.clone(),
+                        Box::new(expr),
+                        self.span_of(consumed),
+                    ),
+                    consumed,
+                ))
+            }
 
-                    // Handling the array assignment part
-                    
-
-                    // In this case, it could be a function call, a++, a--, etc.
-                    // We need to handle this.
-                    let mut handler = ExpressionHandler::new(&self.tokens[self.current..]);
-                    match handler.expression() {
-                        Ok((expr, consumed)) => {
-                            // Make sure a Statement node is pushed.
-
-                            nodes.push(Statement::ExpressionStatement(Box::new(expr)));
-                            self.current += consumed;
-                            continue;
-                        }
-                        Err(e) => return Err(e),
-                    }
-                }
+            // Anything else must be a standalone expression, like `1 + 2;`, a function call or
+            // an array assignment.
+            _ => {
+                let mut handler = ExpressionHandler::new(tokens);
+                let (expr, consumed) = handler.statement()?;
+                Ok((
+                    Statement::ExpressionStatement(Box::new(expr), self.span_of(consumed)),
+                    consumed,
+                ))
+            }
+        }
+    }
+
+    // Records the error and skips to where the next statement is likely to start: past the next
+    // `;` or block, or up to the `}` closing the block we are in.
+    fn recover(&mut self, error: ParseError) {
+        let error_start = match &error {
+            ParseError::UnexpectedToken { span, .. }
+            | ParseError::DuplicateParameter { span, .. }
+            | ParseError::TooDeeplyNested { span } => span.start,
+            ParseError::UnexpectedEof { .. } => usize::MAX,
+        };
+        self.errors.push(error);
+
+        // Whatever was parsed before the token the error points at was fine.
+        let start = self.current;
+        while self
+            .tokens
+            .get(self.current)
+            .is_some_and(|token| token.span.start < error_start)
+        {
+            self.current += 1;
+        }
 
-                if token.token_type == "RBRACE" {
-                    if self.is_inside_brances {
+        let mut depth = 0;
+        while let Some(token) = self.tokens.get(self.current) {
+            match token.kind {
+                TokenKind::Semicolon if depth == 0 => {
+                    self.current += 1;
+                    break;
+                }
+                TokenKind::LBrace => depth += 1,
+                TokenKind::RBrace if depth > 0 => {
+                    depth -= 1;
+                    if depth == 0 {
+                        self.current += 1;
                         break;
                     }
-                    return Err(ParseError::UnexpectedToken {
-                        expected: "Statement".to_string(),
-                        found: token.token_type.to_string(),
-                        line_number: token.line_number,
-                    });
                 }
+                TokenKind::RBrace if self.is_inside_brances => break,
+                _ => {}
             }
+            self.current += 1;
+        }
+
+        // Always make progress, or the same error would be reported forever.
+        if self.current == start {
+            self.current += 1;
         }
+    }
 
-        Ok((nodes, self.current))
+    // The span of the next `consumed` tokens, i.e. of the statement that was just parsed.
+    fn span_of(&self, consumed: usize) -> Span {
+        self.tokens[self.current]
+            .span
+            .to(self.tokens[self.current + consumed - 1].span)
     }
 }
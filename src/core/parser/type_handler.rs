@@ -0,0 +1,75 @@
+use crate::core::parser::ast::Type;
+use crate::core::parser::definition::{ParseError, MAX_NESTING};
+use crate::core::token::{Token, TokenKind};
+
+// Parses a type annotation like `int`, `string` or `[int]`.
+pub struct TypeHandler<'a> {
+    pub start_token: &'a [Token],
+    current: usize,
+    // How many brackets we are in.
+    depth: usize,
+}
+
+impl<'a> TypeHandler<'a> {
+    pub fn new(tokens: &'a [Token]) -> Self {
+        TypeHandler {
+            start_token: tokens,
+            current: 0,
+            depth: 0,
+        }
+    }
+
+    pub fn parse(mut self) -> Result<(Type, usize), ParseError> {
+        let parsed_type = self.handle_type()?;
+        Ok((parsed_type, self.current))
+    }
+
+    fn handle_type(&mut self) -> Result<Type, ParseError> {
+        let token = self
+            .start_token
+            .get(self.current)
+            .ok_or_else(ParseError::unexpected_eof)?;
+        self.current += 1;
+        match &token.kind {
+            // Arrays are written as the element type in brackets, e.g. `[[int]]`.
+            TokenKind::LBracket => {
+                if self.depth == MAX_NESTING {
+                    return Err(ParseError::TooDeeplyNested { span: token.span });
+                }
+                self.depth += 1;
+                let element_type = self.handle_type()?;
+                self.depth -= 1;
+                let token = self
+                    .start_token
+                    .get(self.current)
+                    .ok_or_else(ParseError::unexpected_eof)?;
+                if token.kind != TokenKind::RBracket {
+                    return Err(ParseError::UnexpectedToken {
+                        expected: TokenKind::RBracket.to_string(),
+                        found: token.kind.to_string(),
+                        span: token.span,
+                    });
+                }
+                self.current += 1;
+                Ok(Type::Array(Box::new(element_type)))
+            }
+            TokenKind::Identifier(name) => match name.as_str() {
+                "int" => Ok(Type::Integer),
+                "float" => Ok(Type::Decimal),
+                "string" => Ok(Type::String),
+                "bool" => Ok(Type::Bool),
+                "void" => Ok(Type::Void),
+                _ => Err(ParseError::UnexpectedToken {
+                    expected: "a type".to_string(),
+                    found: token.kind.to_string(),
+                    span: token.span,
+                }),
+            },
+            kind => Err(ParseError::UnexpectedToken {
+                expected: "a type".to_string(),
+                found: kind.to_string(),
+                span: token.span,
+            }),
+        }
+    }
+}
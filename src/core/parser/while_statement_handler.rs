@@ -0,0 +1,40 @@
+use crate::core::parser::ast::Statement;
+use crate::core::parser::definition::ParseError;
+use crate::core::parser::expression_handler::ExpressionHandler;
+use crate::core::parser::statements_handler::StatementsHandler;
+use crate::core::token::Token;
+
+pub struct WhileStatementHandler<'a> {
+    pub start_token: &'a [Token],
+    current: usize,
+}
+
+impl<'a> WhileStatementHandler<'a> {
+    pub fn new(tokens: &'a [Token]) -> Self {
+        WhileStatementHandler {
+            start_token: tokens,
+            current: 1,
+        }
+    }
+
+    pub fn parse(
+        mut self,
+        parent: &mut StatementsHandler,
+    ) -> Result<(Statement, usize), ParseError> {
+        // The first token is `while`, so the condition starts right after it.
+        let mut expression_parser = ExpressionHandler::new(&self.start_token[self.current..]);
+        let (condition, cursor) = expression_parser.expression()?;
+        self.current += cursor;
+
+        let (statements, consumed) = parent.block(&self.start_token[self.current..])?;
+        self.current += consumed;
+
+        let span = self.start_token[0]
+            .span
+            .to(self.start_token[self.current - 1].span);
+        Ok((
+            Statement::WhileStatement(Box::new(condition), statements, span),
+            self.current,
+        ))
+    }
+}
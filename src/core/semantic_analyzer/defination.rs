@@ -1,53 +1,347 @@
-use std::collections::HashMap;
-
-use crate::core::parser::ast::{Expression, Op, Statement, Type};
+use std::{collections::HashMap, fmt};
+
+use crate::core::diagnostics::{closest_match, Diagnostic};
+use crate::core::parser::ast::{Expression, Op, Parameter, Statement, Type};
+use crate::core::semantic_analyzer::host_io::{HostIo, StdIo};
+use crate::core::semantic_analyzer::scopes::Scopes;
+use crate::core::semantic_analyzer::type_checker::{Signature, TypeChecker};
+use crate::core::token::Span;
 use std::sync::{Arc, Mutex};
+use std::{cell::Cell, panic, thread};
+
+// How many function calls may be running at once, so that runaway recursion is an error rather
+// than a crash.
+pub const MAX_CALL_DEPTH: usize = 1000;
+
+// The stack of the thread programs run on. How much of it a call takes depends on how deeply its
+// body nests, so calls also stop once less than STACK_RESERVE of it is left, which is plenty for
+// the deepest nesting the parser accepts.
+const STACK_SIZE: usize = 256 * 1024 * 1024;
+const STACK_RESERVE: usize = 64 * 1024 * 1024;
+
+thread_local! {
+    // The address of the stack where the program started running, or 0 outside of the thread
+    // programs run on.
+    static STACK_START: Cell<usize> = const { Cell::new(0) };
+}
+
+// Checking and running both recurse as deep as the program nests, so they happen on a thread
+// whose stack size is known rather than on whatever thread the host called from. A host that
+// can't spare the stack gets an error pointing at span rather than a panic.
+fn on_interpreter_thread<T: Send>(
+    span: Span,
+    f: impl FnOnce() -> Result<T, AnalysisError> + Send,
+) -> Result<T, AnalysisError> {
+    thread::scope(|scope| {
+        let handle = thread::Builder::new()
+            .stack_size(STACK_SIZE)
+            .spawn_scoped(scope, || {
+                let start = 0u8;
+                STACK_START.with(|cell| cell.set(&start as *const u8 as usize));
+                f()
+            })
+            .map_err(|e| AnalysisError::IoError {
+                message: format!("cannot start the interpreter thread: {}", e),
+                span,
+            })?;
+        handle
+            .join()
+            .unwrap_or_else(|payload| panic::resume_unwind(payload))
+    })
+}
+
+// Roughly how many bytes of the stack the program is using.
+fn stack_in_use() -> usize {
+    let here = 0u8;
+    let here = &here as *const u8 as usize;
+    match STACK_START.with(Cell::get) {
+        0 => 0,
+        start => start.abs_diff(here),
+    }
+}
 
 pub struct Analyzer {
     pub statements: Vec<Statement>,
-    pub variables: Arc<Mutex<HashMap<String, Variable>>>, // HashMap<String, Variable>
+    pub variables: Arc<Mutex<Scopes>>,
+    pub io: Arc<Mutex<dyn HostIo>>,
+    pub functions: Arc<Mutex<HashMap<String, Function>>>,
+    // Whether the statements were type checked along with the rest of the program, so they can
+    // run right away.
+    is_checked: bool,
+}
+
+// How a list of statements finished running. Loops stop on Break and move on to the next
+// iteration on Continue, function calls stop on Return. Everything else passes them up.
+#[derive(Debug, Clone)]
+pub enum ControlFlow {
+    Normal,
+    Break,
+    Continue,
+    Return(ExpressionResult),
+}
+
+// A function declared in the script.
+#[derive(Debug, Clone)]
+pub struct Function {
+    pub parameters: Vec<Parameter>,
+    pub statements: Vec<Statement>,
+    pub return_type: Type,
+    // Where the function is declared, which tells a redeclaration apart from running the same
+    // declaration again.
+    pub span: Span,
 }
 
 #[derive(Debug, Clone)]
 pub enum AnalysisError {
     UndefinedVariable {
         expected: String,
+        // The closest defined variable, in case of a typo.
+        suggestion: Option<String>,
+        span: Span,
     },
     UndefinedFunction {
         expected: String,
         found: String,
+        suggestion: Option<String>,
+        span: Span,
     },
     ArgumentTypeMismatch {
         argument_name: String,
         expected: String,
         found: String,
+        span: Span,
     },
     ArgumentCountMismatch {
         expected: String,
         found: String,
+        span: Span,
     },
     VariableAlreadyDefined {
         variable_name: String,
+        span: Span,
     },
     IllegalOperation {
         expected: String,
         found: String,
         operation: Op,
+        span: Span,
     },
     NonBooleanCondition {
         expected: String,
         found: String,
+        span: Span,
     },
     MismatchedTypes {
         expected: String,
         found: String,
+        span: Span,
     },
     OutOfBoundsException {
         expected: String,
         found: String,
+        span: Span,
+    },
+    IoError {
+        message: String,
+        span: Span,
+    },
+    LoopControlOutsideLoop {
+        keyword: String,
+        span: Span,
+    },
+    ReturnOutsideFunction {
+        span: Span,
+    },
+    CallDepthExceeded {
+        function_name: String,
+        span: Span,
+    },
+    FunctionAlreadyDefined {
+        function_name: String,
+        span: Span,
+    },
+    // An integer result that doesn't fit in 64 bits. The operation is an operator like `+` or the
+    // name of a builtin like `int`.
+    IntegerOverflow {
+        operation: String,
+        span: Span,
+    },
+    DivisionByZero {
+        span: Span,
+    },
+    // An integer raised to a negative power, which would need a decimal result.
+    NegativeExponent {
+        span: Span,
+    },
+    // A string passed to strtoint or strtofloat that isn't a number of the expected type.
+    InvalidNumber {
+        expected: String,
+        found: String,
+        span: Span,
     },
 }
 
+impl AnalysisError {
+    pub fn span(&self) -> Span {
+        match self {
+            AnalysisError::UndefinedVariable { span, .. }
+            | AnalysisError::UndefinedFunction { span, .. }
+            | AnalysisError::ArgumentTypeMismatch { span, .. }
+            | AnalysisError::ArgumentCountMismatch { span, .. }
+            | AnalysisError::VariableAlreadyDefined { span, .. }
+            | AnalysisError::IllegalOperation { span, .. }
+            | AnalysisError::NonBooleanCondition { span, .. }
+            | AnalysisError::MismatchedTypes { span, .. }
+            | AnalysisError::OutOfBoundsException { span, .. }
+            | AnalysisError::IoError { span, .. }
+            | AnalysisError::LoopControlOutsideLoop { span, .. }
+            | AnalysisError::ReturnOutsideFunction { span }
+            | AnalysisError::CallDepthExceeded { span, .. }
+            | AnalysisError::FunctionAlreadyDefined { span, .. }
+            | AnalysisError::IntegerOverflow { span, .. }
+            | AnalysisError::DivisionByZero { span }
+            | AnalysisError::NegativeExponent { span }
+            | AnalysisError::InvalidNumber { span, .. } => *span,
+        }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        let message = match self {
+            AnalysisError::UndefinedVariable { expected, .. } => {
+                format!("undefined variable `{}`", expected)
+            }
+            AnalysisError::UndefinedFunction { expected, .. } => {
+                format!("undefined function `{}`", expected)
+            }
+            AnalysisError::ArgumentTypeMismatch {
+                argument_name,
+                expected,
+                found,
+                ..
+            } => format!(
+                "argument {} expected {} but found {}",
+                argument_name, expected, found
+            ),
+            AnalysisError::ArgumentCountMismatch {
+                expected, found, ..
+            } => format!("expected {} arguments but found {}", expected, found),
+            AnalysisError::VariableAlreadyDefined { variable_name, .. } => {
+                format!("variable `{}` is already defined", variable_name)
+            }
+            AnalysisError::IllegalOperation {
+                expected,
+                found,
+                operation,
+                ..
+            } => format!(
+                "illegal operation `{}`: expected {} but found {}",
+                operation, expected, found
+            ),
+            AnalysisError::NonBooleanCondition {
+                expected, found, ..
+            } => format!("condition must be {} but found {}", expected, found),
+            AnalysisError::MismatchedTypes {
+                expected, found, ..
+            } => format!(
+                "mismatched types: expected {} but found {}",
+                expected, found
+            ),
+            AnalysisError::OutOfBoundsException {
+                expected, found, ..
+            } => format!(
+                "index {} is out of bounds for an array of length {}",
+                found, expected
+            ),
+            AnalysisError::IoError { message, .. } => format!("I/O error: {}", message),
+            AnalysisError::LoopControlOutsideLoop { keyword, .. } => {
+                format!("`{}` outside of a loop", keyword)
+            }
+            AnalysisError::ReturnOutsideFunction { .. } => {
+                "`return` outside of a function".to_string()
+            }
+            AnalysisError::CallDepthExceeded { function_name, .. } => {
+                format!("too many nested calls when calling `{}`", function_name)
+            }
+            AnalysisError::FunctionAlreadyDefined { function_name, .. } => {
+                format!("function `{}` is already defined", function_name)
+            }
+            AnalysisError::IntegerOverflow { operation, .. } => {
+                format!("integer overflow in `{}`", operation)
+            }
+            AnalysisError::DivisionByZero { .. } => "division by zero".to_string(),
+            AnalysisError::NegativeExponent { .. } => {
+                "integer raised to a negative power".to_string()
+            }
+            AnalysisError::InvalidNumber {
+                expected, found, ..
+            } => {
+                format!("cannot convert {:?} to {}", found, expected)
+            }
+        };
+
+        let help = match self {
+            AnalysisError::UndefinedVariable { suggestion, .. }
+            | AnalysisError::UndefinedFunction { suggestion, .. } => suggestion
+                .as_ref()
+                .map(|suggestion| format!("did you mean `{}`?", suggestion)),
+            AnalysisError::VariableAlreadyDefined { .. } => {
+                Some("use `=` instead of `let` to assign a new value".to_string())
+            }
+            // Only the arithmetic operators have builtins to go to.
+            AnalysisError::IntegerOverflow { operation, .. }
+                if matches!(operation.as_str(), "+" | "-" | "*" | "/") =>
+            {
+                Some(
+                    "use the wrapping_*, saturating_* or checked_* builtins for other behaviour"
+                        .to_string(),
+                )
+            }
+            AnalysisError::NegativeExponent { .. } => {
+                Some("use a decimal base, like `2.0 ** -1`".to_string())
+            }
+            AnalysisError::OutOfBoundsException { found, .. } if found.starts_with('-') => {
+                Some("indexes start at 0".to_string())
+            }
+            AnalysisError::CallDepthExceeded { .. } => Some(format!(
+                "at most {} calls can run at once, check that the recursion reaches a case \
+                     that returns",
+                MAX_CALL_DEPTH
+            )),
+            _ => None,
+        };
+
+        let title = if self.is_runtime() {
+            "runtime error"
+        } else {
+            "error"
+        };
+        Diagnostic::new(title, message, self.span()).with_help(help)
+    }
+
+    // Errors that can only be detected while the program is running, as opposed to errors in
+    // the program itself.
+    pub fn is_runtime(&self) -> bool {
+        matches!(
+            self,
+            AnalysisError::OutOfBoundsException { .. }
+                | AnalysisError::IoError { .. }
+                | AnalysisError::IntegerOverflow { .. }
+                | AnalysisError::DivisionByZero { .. }
+                | AnalysisError::NegativeExponent { .. }
+                | AnalysisError::CallDepthExceeded { .. }
+                | AnalysisError::InvalidNumber { .. }
+        )
+    }
+}
+
+impl fmt::Display for AnalysisError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.diagnostic())
+    }
+}
+
+impl std::error::Error for AnalysisError {}
+
 pub struct Variable {
     pub name: String,
     value: ExpressionValue,
@@ -56,44 +350,132 @@ pub struct Variable {
 
 impl Analyzer {
     pub fn new(statements: Vec<Statement>) -> Self {
-        let variables = HashMap::new();
         Analyzer {
             statements,
-            variables: Arc::new(Mutex::new(variables)),
+            variables: Arc::new(Mutex::new(Scopes::new())),
+            io: Arc::new(Mutex::new(StdIo)),
+            functions: Arc::new(Mutex::new(HashMap::new())),
+            is_checked: false,
         }
     }
 
-    fn set_variables(&mut self, variables: Arc<Mutex<HashMap<String, Variable>>>) {
+    // Runs a block nested in this one, sharing its variables, functions and I/O. The block gets a
+    // scope of its own, so its variables are dropped once it ends, however it ends.
+    fn run_block(&self, statements: &[Statement]) -> Result<ControlFlow, AnalysisError> {
+        let mut analyzer = Analyzer::new(statements.to_vec());
+        analyzer.set_variables(self.variables.clone());
+        analyzer.set_io(self.io.clone());
+        analyzer.functions = self.functions.clone();
+        analyzer.is_checked = true;
+
+        self.variables.lock().unwrap().push();
+        let result = analyzer.parse();
+        self.variables.lock().unwrap().pop();
+        result
+    }
+
+    fn set_variables(&mut self, variables: Arc<Mutex<Scopes>>) {
         self.variables = variables;
     }
 
-    pub fn parse(&mut self) -> Result<bool, AnalysisError> {
+    pub fn set_io(&mut self, io: Arc<Mutex<dyn HostIo>>) {
+        self.io = io;
+    }
+
+    pub fn parse(&mut self) -> Result<ControlFlow, AnalysisError> {
+        if self.is_checked {
+            return self.run();
+        }
+
+        let span = self
+            .statements
+            .first()
+            .map(Statement::span)
+            .unwrap_or_default();
+        on_interpreter_thread(span, || {
+            // Every statement is type checked before any of them runs, including the ones that
+            // never will.
+            self.check()?;
+            self.run()
+        })
+    }
+
+    fn run(&mut self) -> Result<ControlFlow, AnalysisError> {
+        // Like the type checker, functions can be called before they are declared, so the ones
+        // declared in this block are defined before any of it runs. A block that runs again, like
+        // a loop body or a function body, finds its own declarations already there.
+        for statement in &self.statements {
+            if let Statement::FunctionDeclaration(name, parameters, statements, return_type, span) =
+                statement
+            {
+                let mut functions_guard = self.functions.lock().unwrap();
+                if functions_guard
+                    .get(name)
+                    .is_some_and(|function| function.span == *span)
+                {
+                    continue;
+                }
+                if functions_guard.contains_key(name) || load_native_functions().contains_key(name)
+                {
+                    return Err(AnalysisError::FunctionAlreadyDefined {
+                        function_name: name.to_string(),
+                        span: *span,
+                    });
+                }
+                functions_guard.insert(
+                    name.to_string(),
+                    Function {
+                        parameters: parameters.clone(),
+                        statements: statements.clone(),
+                        return_type: return_type.clone().unwrap_or(Type::Void),
+                        span: *span,
+                    },
+                );
+            }
+        }
+
         for statement in &self.statements {
             match statement {
-                Statement::Let(var_name, expression) => {
+                Statement::Let(var_name, annotation, expression, _) => {
                     // check if the variable is already defined
                     // if not, then add it to the variables
                     let variables_guard = self.variables.lock().unwrap();
-                    if variables_guard.contains_key(var_name) {
+                    if variables_guard.is_declared_here(var_name) {
                         drop(variables_guard);
                         return Err(AnalysisError::VariableAlreadyDefined {
                             variable_name: var_name.to_string(),
+                            span: statement.span(),
                         });
                     }
 
                     drop(variables_guard);
-                    let mut expression_type_evaluator =
-                        ExpressionTypeEvaluator::new(*expression.clone(), self.variables.clone());
+                    let mut expression_type_evaluator = ExpressionTypeEvaluator::new(
+                        *expression.clone(),
+                        self.variables.clone(),
+                        self.io.clone(),
+                        self.functions.clone(),
+                    );
 
                     match expression_type_evaluator.parse() {
                         Ok(expression_type) => {
+                            let variable_type = match annotation {
+                                Some(annotation) => annotation
+                                    .coerce(&expression_type.expression_type)
+                                    .ok_or_else(|| AnalysisError::MismatchedTypes {
+                                        expected: annotation.to_string(),
+                                        found: expression_type.expression_type.to_string(),
+                                        span: expression.span(),
+                                    })?,
+                                None => expression_type.expression_type.clone(),
+                            };
+                            let value = coerce_value(expression_type, &variable_type).value;
                             let mut variables_guard = self.variables.lock().unwrap();
                             variables_guard.insert(
                                 var_name.to_string(),
                                 Variable {
                                     name: var_name.to_string(),
-                                    value: expression_type.value,
-                                    variable_type: expression_type.expression_type,
+                                    value,
+                                    variable_type,
                                 },
                             );
                             drop(variables_guard);
@@ -101,27 +483,39 @@ impl Analyzer {
                         Err(e) => return Err(e),
                     }
                 }
-                Statement::Assignment(var_name, _expression) => {
+                Statement::Assignment(var_name, _expression, _) => {
                     // check if the variable is already defined
                     let variables_guard = self.variables.lock().unwrap();
                     if !variables_guard.contains_key(var_name) {
-                        drop(variables_guard);
                         return Err(AnalysisError::UndefinedVariable {
                             expected: var_name.to_string(),
+                            suggestion: closest_match(
+                                var_name,
+                                variables_guard.keys().map(String::as_str),
+                            ),
+                            span: statement.span(),
                         });
                     }
 
                     drop(variables_guard);
-                    let mut expression_type_evaluator =
-                        ExpressionTypeEvaluator::new(*_expression.clone(), self.variables.clone());
+                    let mut expression_type_evaluator = ExpressionTypeEvaluator::new(
+                        *_expression.clone(),
+                        self.variables.clone(),
+                        self.io.clone(),
+                        self.functions.clone(),
+                    );
                     match expression_type_evaluator.parse() {
                         Ok(_) => {}
                         Err(e) => return Err(e),
                     }
                 }
-                Statement::ExpressionStatement(expression) => {
-                    let mut expression_type_evaluator =
-                        ExpressionTypeEvaluator::new(*expression.clone(), self.variables.clone());
+                Statement::ExpressionStatement(expression, _) => {
+                    let mut expression_type_evaluator = ExpressionTypeEvaluator::new(
+                        *expression.clone(),
+                        self.variables.clone(),
+                        self.io.clone(),
+                        self.functions.clone(),
+                    );
                     match expression_type_evaluator.parse() {
                         Ok(_expression_type) => {
                             // Do nothing
@@ -129,25 +523,33 @@ impl Analyzer {
                         Err(e) => return Err(e),
                     }
                 }
-                Statement::IfStatement(condition, _statements) => {
-                    let mut expression_type_evaluator =
-                        ExpressionTypeEvaluator::new(*condition.clone(), self.variables.clone());
+                Statement::IfStatement(condition, _statements, else_statements, _) => {
+                    let mut expression_type_evaluator = ExpressionTypeEvaluator::new(
+                        *condition.clone(),
+                        self.variables.clone(),
+                        self.io.clone(),
+                        self.functions.clone(),
+                    );
                     match expression_type_evaluator.parse() {
                         Ok(expression_type) => {
                             if expression_type.expression_type != Type::Bool {
                                 return Err(AnalysisError::NonBooleanCondition {
                                     expected: "Boolean".to_string(),
                                     found: expression_type.expression_type.to_string(),
+                                    span: statement.span(),
                                 });
                             }
 
-                            if let ExpressionValue::Bool(true) = expression_type.value {
-                                let mut analyzer = Analyzer::new(_statements.clone());
-                                analyzer.set_variables(self.variables.clone());
-                                match analyzer.parse() {
-                                    Ok(_) => {
-                                        // Do nothing
-                                    }
+                            let branch = match expression_type.value {
+                                ExpressionValue::Bool(true) => Some(_statements),
+                                _ => else_statements.as_ref(),
+                            };
+                            if let Some(branch) = branch {
+                                match self.run_block(branch) {
+                                    // A break or continue inside of the branch is meant for
+                                    // the enclosing loop.
+                                    Ok(ControlFlow::Normal) => {}
+                                    Ok(control_flow) => return Ok(control_flow),
                                     Err(e) => return Err(e),
                                 }
                             }
@@ -155,10 +557,20 @@ impl Analyzer {
                         Err(e) => return Err(e),
                     }
                 }
-                Statement::ForStatement(init_expr, condition_expr, increment_expr, statements) => {
+                Statement::ForStatement(
+                    init_expr,
+                    condition_expr,
+                    increment_expr,
+                    statements,
+                    _,
+                ) => {
                     // execute the init expression
-                    let mut expression_type_evaluator =
-                        ExpressionTypeEvaluator::new(*init_expr.clone(), self.variables.clone());
+                    let mut expression_type_evaluator = ExpressionTypeEvaluator::new(
+                        *init_expr.clone(),
+                        self.variables.clone(),
+                        self.io.clone(),
+                        self.functions.clone(),
+                    );
                     match expression_type_evaluator.parse() {
                         Ok(_expression_type) => {
                             // Do nothing
@@ -170,6 +582,8 @@ impl Analyzer {
                     let mut expression_type_evaluator = ExpressionTypeEvaluator::new(
                         *condition_expr.clone(),
                         self.variables.clone(),
+                        self.io.clone(),
+                        self.functions.clone(),
                     );
 
                     match expression_type_evaluator.parse() {
@@ -178,6 +592,7 @@ impl Analyzer {
                                 return Err(AnalysisError::NonBooleanCondition {
                                     expected: "Boolean".to_string(),
                                     found: expression_type.expression_type.to_string(),
+                                    span: statement.span(),
                                 });
                             }
 
@@ -188,12 +603,11 @@ impl Analyzer {
                             }
 
                             while condition_value {
-                                let mut analyzer = Analyzer::new(statements.clone());
-                                analyzer.set_variables(self.variables.clone());
-                                match analyzer.parse() {
-                                    Ok(_) => {
-                                        // Do nothing
-                                    }
+                                match self.run_block(statements) {
+                                    Ok(ControlFlow::Break) => break,
+                                    // Continue still runs the increment expression.
+                                    Ok(ControlFlow::Normal | ControlFlow::Continue) => {}
+                                    Ok(control_flow) => return Ok(control_flow),
                                     Err(e) => return Err(e),
                                 }
 
@@ -201,6 +615,8 @@ impl Analyzer {
                                 let mut expression_type_evaluator = ExpressionTypeEvaluator::new(
                                     *increment_expr.clone(),
                                     self.variables.clone(),
+                                    self.io.clone(),
+                                    self.functions.clone(),
                                 );
                                 // print increment expressions
                                 match expression_type_evaluator.parse() {
@@ -212,6 +628,8 @@ impl Analyzer {
                                 let mut expression_type_evaluator = ExpressionTypeEvaluator::new(
                                     *condition_expr.clone(),
                                     self.variables.clone(),
+                                    self.io.clone(),
+                                    self.functions.clone(),
                                 );
                                 match expression_type_evaluator.parse() {
                                     Ok(expression_type) => {
@@ -219,6 +637,7 @@ impl Analyzer {
                                             return Err(AnalysisError::NonBooleanCondition {
                                                 expected: "Boolean".to_string(),
                                                 found: expression_type.expression_type.to_string(),
+                                                span: statement.span(),
                                             });
                                         }
                                         if let ExpressionValue::Bool(value) = expression_type.value
@@ -228,6 +647,7 @@ impl Analyzer {
                                             return Err(AnalysisError::NonBooleanCondition {
                                                 expected: "Boolean".to_string(),
                                                 found: expression_type.expression_type.to_string(),
+                                                span: statement.span(),
                                             });
                                         }
                                     }
@@ -238,42 +658,67 @@ impl Analyzer {
                         Err(e) => return Err(e),
                     }
                 }
-                Statement::ArrayAssignment(var_name, index_expression, assignment_expression) => {
+                Statement::WhileStatement(condition, statements, _) => {
+                    while self.evaluate_condition(condition)? {
+                        match self.run_block(statements)? {
+                            ControlFlow::Break => break,
+                            ControlFlow::Normal | ControlFlow::Continue => {}
+                            control_flow => return Ok(control_flow),
+                        }
+                    }
+                }
+                Statement::Break(_) => return Ok(ControlFlow::Break),
+                Statement::Continue(_) => return Ok(ControlFlow::Continue),
+                Statement::ArrayAssignment(
+                    var_name,
+                    index_expression,
+                    assignment_expression,
+                    _,
+                ) => {
                     // check if the variable is already defined
                     let variables_guard = self.variables.lock().unwrap();
                     if !variables_guard.contains_key(var_name) {
-                        drop(variables_guard);
                         return Err(AnalysisError::UndefinedVariable {
                             expected: var_name.to_string(),
+                            suggestion: closest_match(
+                                var_name,
+                                variables_guard.keys().map(String::as_str),
+                            ),
+                            span: statement.span(),
                         });
                     }
 
                     drop(variables_guard);
-                    let mut expression_type_evaluator =
-                        ExpressionTypeEvaluator::new(*index_expression.clone(), self.variables.clone());
-                    let index = match expression_type_evaluator.parse() {
-                        Ok(val) => val,
-                        Err(e) => return Err(e),
-                    };
+                    let mut expression_type_evaluator = ExpressionTypeEvaluator::new(
+                        *index_expression.clone(),
+                        self.variables.clone(),
+                        self.io.clone(),
+                        self.functions.clone(),
+                    );
+                    let index = expression_type_evaluator.parse()?;
 
-                    let mut expression_type_evaluator =
-                        ExpressionTypeEvaluator::new(*assignment_expression.clone(), self.variables.clone());
-                    let new_value = match expression_type_evaluator.parse() {
-                        Ok(val) => val,
-                        Err(e) => return Err(e),
-                    };
+                    let mut expression_type_evaluator = ExpressionTypeEvaluator::new(
+                        *assignment_expression.clone(),
+                        self.variables.clone(),
+                        self.io.clone(),
+                        self.functions.clone(),
+                    );
+                    let new_value = expression_type_evaluator.parse()?;
 
                     println!("here....");
 
                     let mut variables_guard = self.variables.lock().unwrap();
                     if let Some(variable) = variables_guard.get_mut(var_name) {
-                        if let Type::Array(inner_type) = &variable.variable_type {
-                            if new_value.expression_type != **inner_type {
+                        if let Type::Array(inner_type) = variable.variable_type.clone() {
+                            let Some(inner_type) = inner_type.coerce(&new_value.expression_type)
+                            else {
                                 return Err(AnalysisError::MismatchedTypes {
                                     expected: inner_type.to_string(),
                                     found: new_value.expression_type.to_string(),
+                                    span: statement.span(),
                                 });
-                            }
+                            };
+                            variable.variable_type = Type::Array(Box::new(inner_type.clone()));
                             println!("hrer....");
 
                             if let ExpressionValue::Array(array) = &mut variable.value {
@@ -282,137 +727,455 @@ impl Analyzer {
                                         return Err(AnalysisError::OutOfBoundsException {
                                             expected: array.len().to_string(),
                                             found: index.to_string(),
+                                            span: statement.span(),
                                         });
                                     }
 
                                     println!("new value is {:?}", new_value);
 
-                                    array[index as usize] = new_value;
+                                    array[index as usize] = coerce_value(new_value, &inner_type);
                                 }
                             }
                         }
                     }
                     drop(variables_guard);
                 }
-                Statement::FunctionDeclaration(_, _, _, _) => {}
+                // Already defined above.
+                Statement::FunctionDeclaration(..) => {}
+                Statement::Return(expression, _) => {
+                    let result = match expression {
+                        Some(expression) => self.evaluate_here(expression)?,
+                        None => ExpressionResult {
+                            value: ExpressionValue::String("".to_string()),
+                            expression_type: Type::Void,
+                        },
+                    };
+                    return Ok(ControlFlow::Return(result));
+                }
             }
         }
 
-        return Ok(true);
+        Ok(ControlFlow::Normal)
+    }
+
+    // Type checks the statements against the variables and functions defined so far, without
+    // running anything.
+    pub fn check(&mut self) -> Result<(), AnalysisError> {
+        let mut type_checker = TypeChecker::new();
+        for (name, variable) in self.variables.lock().unwrap().iter() {
+            type_checker.declare_variable(name, variable.variable_type.clone());
+        }
+        for (name, function) in self.functions.lock().unwrap().iter() {
+            type_checker.declare_function(
+                name,
+                Signature::new(&function.parameters, function.return_type.clone()),
+            );
+        }
+        type_checker.check(&mut self.statements)
+    }
+
+    // Evaluates the condition of an if or a loop, which has to be a boolean.
+    fn evaluate_condition(&self, condition: &Expression) -> Result<bool, AnalysisError> {
+        let result = self.evaluate_here(condition)?;
+        match result.value {
+            ExpressionValue::Bool(value) => Ok(value),
+            _ => Err(AnalysisError::NonBooleanCondition {
+                expected: "Boolean".to_string(),
+                found: result.expression_type.to_string(),
+                span: condition.span(),
+            }),
+        }
+    }
+
+    // Evaluates a single expression against the current variables, e.g. to show the value of an
+    // expression statement in the REPL.
+    pub fn evaluate(&self, expression: &Expression) -> Result<ExpressionResult, AnalysisError> {
+        on_interpreter_thread(expression.span(), || self.evaluate_here(expression))
+    }
+
+    // Same as evaluate, on the thread the program is already running on.
+    fn evaluate_here(&self, expression: &Expression) -> Result<ExpressionResult, AnalysisError> {
+        ExpressionTypeEvaluator::new(
+            expression.clone(),
+            self.variables.clone(),
+            self.io.clone(),
+            self.functions.clone(),
+        )
+        .parse()
+    }
+}
+
+// Turns an integer operand into a decimal when the other one is a decimal, so that mixed
+// arithmetic like `1 + 2.5` is done on decimals and gives 3.5.
+fn promote_operands(
+    first: ExpressionResult,
+    second: ExpressionResult,
+) -> (ExpressionResult, ExpressionResult) {
+    match (&first.expression_type, &second.expression_type) {
+        (Type::Integer, Type::Decimal) => (coerce_value(first, &Type::Decimal), second),
+        (Type::Decimal, Type::Integer) => (first, coerce_value(second, &Type::Decimal)),
+        _ => (first, second),
+    }
+}
+
+// Converts a value to the type it is stored as, which only changes anything when integers are
+// widened to decimals, on their own or inside of arrays.
+fn coerce_value(result: ExpressionResult, to: &Type) -> ExpressionResult {
+    match (to, result.value) {
+        (Type::Decimal, ExpressionValue::Integer(value)) => ExpressionResult {
+            value: ExpressionValue::Decimal(value as f64),
+            expression_type: Type::Decimal,
+        },
+        (Type::Array(element_type), ExpressionValue::Array(elements)) => ExpressionResult {
+            value: ExpressionValue::Array(
+                elements
+                    .into_iter()
+                    .map(|element| coerce_value(element, element_type))
+                    .collect(),
+            ),
+            expression_type: to
+                .coerce(&result.expression_type)
+                .unwrap_or(result.expression_type),
+        },
+        (_, value) => ExpressionResult {
+            value,
+            expression_type: result.expression_type,
+        },
+    }
+}
+
+// How long an array can grow by storing past its end, so that a stray `a[1000000000] = 0` is an
+// error rather than a program using up all the memory.
+const MAX_ARRAY_LENGTH: usize = 1 << 20;
+
+// The value an array is padded with when something is stored past its end.
+fn default_value(value_type: &Type) -> ExpressionResult {
+    let value = match value_type {
+        Type::Integer => ExpressionValue::Integer(0),
+        Type::Decimal => ExpressionValue::Decimal(0.0),
+        Type::Bool => ExpressionValue::Bool(false),
+        Type::Array(_) => ExpressionValue::Array(vec![]),
+        Type::String | Type::Void | Type::Any => ExpressionValue::String("".to_string()),
+    };
+    ExpressionResult {
+        value,
+        expression_type: value_type.clone(),
+    }
+}
+
+// Whether two values are equal, or None if they can't be compared because their types differ.
+fn values_are_equal(first: &ExpressionResult, second: &ExpressionResult) -> Option<bool> {
+    first.expression_type.promote(&second.expression_type)?;
+    match (&first.value, &second.value) {
+        (ExpressionValue::String(first), ExpressionValue::String(second)) => Some(first == second),
+        (ExpressionValue::Integer(first), ExpressionValue::Integer(second)) => {
+            Some(first == second)
+        }
+        (ExpressionValue::Decimal(first), ExpressionValue::Decimal(second)) => {
+            Some(first == second)
+        }
+        (ExpressionValue::Integer(first), ExpressionValue::Decimal(second))
+        | (ExpressionValue::Decimal(second), ExpressionValue::Integer(first)) => {
+            Some(*first as f64 == *second)
+        }
+        (ExpressionValue::Bool(first), ExpressionValue::Bool(second)) => Some(first == second),
+        (ExpressionValue::Array(first), ExpressionValue::Array(second)) => {
+            if first.len() != second.len() {
+                return Some(false);
+            }
+            let mut result = true;
+            for (first, second) in first.iter().zip(second) {
+                result &= values_are_equal(first, second)?;
+            }
+            Some(result)
+        }
+        _ => None,
     }
 }
 
 #[derive(Debug, Clone)]
-struct ExpressionResult {
-    value: ExpressionValue,
-    expression_type: Type,
+pub struct ExpressionResult {
+    pub value: ExpressionValue,
+    pub expression_type: Type,
 }
 
 #[derive(Debug, Clone)]
-enum ExpressionValue {
+pub enum ExpressionValue {
     String(String),
-    Integer(i32),
-    Decimal(f32),
+    Integer(i64),
+    Decimal(f64),
     Bool(bool),
     Array(Vec<ExpressionResult>),
 }
 
+impl fmt::Display for ExpressionValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExpressionValue::String(value) => write!(f, "{}", value),
+            ExpressionValue::Integer(value) => write!(f, "{}", value),
+            ExpressionValue::Decimal(value) => write!(f, "{}", value),
+            ExpressionValue::Bool(value) => write!(f, "{}", value),
+            ExpressionValue::Array(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", value.value)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
 struct ExpressionTypeEvaluator {
     pub expression: Expression,
-    variables: Arc<Mutex<HashMap<String, Variable>>>,
+    variables: Arc<Mutex<Scopes>>,
+    io: Arc<Mutex<dyn HostIo>>,
+    functions: Arc<Mutex<HashMap<String, Function>>>,
 }
 
 impl ExpressionTypeEvaluator {
-    pub fn new(expression: Expression, variables: Arc<Mutex<HashMap<String, Variable>>>) -> Self {
+    pub fn new(
+        expression: Expression,
+        variables: Arc<Mutex<Scopes>>,
+        io: Arc<Mutex<dyn HostIo>>,
+        functions: Arc<Mutex<HashMap<String, Function>>>,
+    ) -> Self {
         ExpressionTypeEvaluator {
             expression,
             variables,
+            io,
+            functions,
         }
     }
 
-    fn parse(&mut self) -> Result<ExpressionResult, AnalysisError> {
-        match &self.expression {
-            Expression::StringLiteral(value, _type) => {
-                return Ok(ExpressionResult {
-                    value: ExpressionValue::String(value.to_string()),
-                    expression_type: Type::String,
+    // Calls a function declared in the script. The body runs with its own variables, holding
+    // just the arguments, so that it can't clobber the variables of the caller.
+    fn call_function(
+        &self,
+        function_name: &str,
+        function: Function,
+        arguments: &[Expression],
+    ) -> Result<ExpressionResult, AnalysisError> {
+        if function.parameters.len() != arguments.len() {
+            return Err(AnalysisError::ArgumentCountMismatch {
+                expected: function.parameters.len().to_string(),
+                found: arguments.len().to_string(),
+                span: self.expression.span(),
+            });
+        }
+
+        // The arguments are evaluated in the scope of the caller, before the body gets a scope of
+        // its own.
+        let mut parameters = vec![];
+        for (parameter, argument) in function.parameters.iter().zip(arguments) {
+            let Parameter::Identifier(name, parameter_type) = parameter;
+            let mut expression_type_evaluator = ExpressionTypeEvaluator::new(
+                argument.clone(),
+                self.variables.clone(),
+                self.io.clone(),
+                self.functions.clone(),
+            );
+            let result = expression_type_evaluator.parse()?;
+            let Some(variable_type) = parameter_type.coerce(&result.expression_type) else {
+                return Err(AnalysisError::ArgumentTypeMismatch {
+                    argument_name: name.to_string(),
+                    expected: parameter_type.to_string(),
+                    found: result.expression_type.to_string(),
+                    span: argument.span(),
                 });
+            };
+            parameters.push(Variable {
+                name: name.to_string(),
+                value: coerce_value(result, &variable_type).value,
+                variable_type,
+            });
+        }
+
+        let mut variables_guard = self.variables.lock().unwrap();
+        if variables_guard.call_depth() >= MAX_CALL_DEPTH
+            || stack_in_use() > STACK_SIZE - STACK_RESERVE
+        {
+            return Err(AnalysisError::CallDepthExceeded {
+                function_name: function_name.to_string(),
+                span: self.expression.span(),
+            });
+        }
+        variables_guard.push_function();
+        for parameter in parameters {
+            variables_guard.insert(parameter.name.clone(), parameter);
+        }
+        drop(variables_guard);
+
+        let mut analyzer = Analyzer::new(function.statements);
+        analyzer.set_variables(self.variables.clone());
+        analyzer.set_io(self.io.clone());
+        analyzer.functions = self.functions.clone();
+        analyzer.is_checked = true;
+        let result = analyzer.parse();
+        self.variables.lock().unwrap().pop_function();
+
+        // Falling off the end of the body is the same as `return;`.
+        let mut result = match result? {
+            ControlFlow::Return(result) => result,
+            _ => ExpressionResult {
+                value: ExpressionValue::String("".to_string()),
+                expression_type: Type::Void,
+            },
+        };
+        match function.return_type.coerce(&result.expression_type) {
+            Some(return_type) => result = coerce_value(result, &return_type),
+            None => {
+                return Err(AnalysisError::MismatchedTypes {
+                    expected: format!("{} returned from `{}`", function.return_type, function_name),
+                    found: result.expression_type.to_string(),
+                    span: self.expression.span(),
+                })
             }
-            Expression::Number(value, _type) => {
-                // check if the number is decimal
-                if value.contains(".") {
-                    let parsed_num = value.parse::<f32>();
+        }
+        Ok(result)
+    }
+
+    // Applies one of the operators that work on integers apart from + - * and /, raising an error
+    // where Rust would panic or the result doesn't fit. `>>` keeps the sign, so `-8 >> 1` is -4.
+    fn integer_operation(
+        &self,
+        operator: &Op,
+        first: i64,
+        second: i64,
+    ) -> Result<i64, AnalysisError> {
+        let span = self.expression.span();
+        let overflow = || AnalysisError::IntegerOverflow {
+            operation: operator.to_string(),
+            span,
+        };
+        match operator {
+            Op::Modulo if second == 0 => Err(AnalysisError::DivisionByZero { span }),
+            Op::Modulo => first.checked_rem(second).ok_or_else(overflow),
+            Op::Power if second < 0 => Err(AnalysisError::NegativeExponent { span }),
+            Op::Power => {
+                // Only 0, 1 and -1 can be raised to a power this big without overflowing, and for
+                // those only whether the exponent is odd matters.
+                let exponent = if second > u32::MAX as i64 && (-1..=1).contains(&first) {
+                    2 + second % 2
+                } else {
+                    second
+                };
+                u32::try_from(exponent)
+                    .ok()
+                    .and_then(|exponent| first.checked_pow(exponent))
+                    .ok_or_else(overflow)
+            }
+            Op::BitwiseAnd => Ok(first & second),
+            Op::BitwiseOr => Ok(first | second),
+            Op::BitwiseXor => Ok(first ^ second),
+            // Shifting by a negative amount or by 64 bits or more is an overflow.
+            Op::ShiftLeft => u32::try_from(second)
+                .ok()
+                .and_then(|amount| first.checked_shl(amount))
+                .ok_or_else(overflow),
+            Op::ShiftRight => u32::try_from(second)
+                .ok()
+                .and_then(|amount| first.checked_shr(amount))
+                .ok_or_else(overflow),
+            _ => Err(AnalysisError::IllegalOperation {
+                expected: "an integer operator".to_string(),
+                found: format!("`{}`", operator),
+                operation: operator.clone(),
+                span,
+            }),
+        }
+    }
+
+    fn parse(&mut self) -> Result<ExpressionResult, AnalysisError> {
+        match &self.expression {
+            Expression::StringLiteral(value, _type, _) => Ok(ExpressionResult {
+                value: ExpressionValue::String(value.to_string()),
+                expression_type: Type::String,
+            }),
+            Expression::Number(value, _type, _) => {
+                // check if the number is decimal, the lexer has already turned hex, binary and
+                // octal literals into plain integers
+                if value.contains(['.', 'e', 'E']) {
+                    let parsed_num = value.parse::<f64>();
                     return Ok(ExpressionResult {
                         value: ExpressionValue::Decimal(parsed_num.unwrap()),
                         expression_type: Type::Decimal,
                     });
                 }
-                let parsed_num = value.parse::<i32>().unwrap();
-                return Ok(ExpressionResult {
+                let parsed_num = value.parse::<i64>().unwrap();
+                Ok(ExpressionResult {
                     value: ExpressionValue::Integer(parsed_num),
                     expression_type: Type::Integer,
-                });
-            }
-            Expression::Boolean(value, _type) => {
-                return Ok(ExpressionResult {
-                    value: ExpressionValue::Bool(value.to_string() == "true"),
-                    expression_type: Type::Bool,
-                });
+                })
             }
-            Expression::Array(expressions, _) => {
+            Expression::Boolean(value, _type, _) => Ok(ExpressionResult {
+                value: ExpressionValue::Bool(value.to_string() == "true"),
+                expression_type: Type::Bool,
+            }),
+            // The element type is whatever all of the elements have in common, and unknown for
+            // an empty array until something is stored in it.
+            Expression::Array(expressions, _, _) => {
                 let mut array = vec![];
-                let mut last_type = Type::Void;
+                let mut element_type = Type::Any;
                 for expression in expressions {
-                    let mut expression_type_evaluator =
-                        ExpressionTypeEvaluator::new(expression.clone(), self.variables.clone());
-                    match expression_type_evaluator.parse() {
-                        Ok(expression) => {
-                            let expression_type = expression.expression_type.clone();
-                            if last_type != Type::Void && last_type != expression_type {
-                                return Err(AnalysisError::MismatchedTypes {
-                                    expected: last_type.to_string(),
-                                    found: expression_type.to_string(),
-                                });
-                            }
-                            last_type = expression_type;
-                            array.push(expression);
+                    let mut expression_type_evaluator = ExpressionTypeEvaluator::new(
+                        expression.clone(),
+                        self.variables.clone(),
+                        self.io.clone(),
+                        self.functions.clone(),
+                    );
+                    let element = expression_type_evaluator.parse()?;
+                    element_type = match element_type.promote(&element.expression_type) {
+                        Some(element_type) => element_type,
+                        None => {
+                            return Err(AnalysisError::MismatchedTypes {
+                                expected: element_type.to_string(),
+                                found: element.expression_type.to_string(),
+                                span: expression.span(),
+                            })
                         }
-                        Err(e) => return Err(e),
-                    }
+                    };
+                    array.push(element);
                 }
 
-                return Ok(ExpressionResult {
+                let array = array
+                    .into_iter()
+                    .map(|element| coerce_value(element, &element_type))
+                    .collect();
+                Ok(ExpressionResult {
                     value: ExpressionValue::Array(array),
-                    expression_type: Type::Array(Box::new(Type::Integer)),
-                });
+                    expression_type: Type::Array(Box::new(element_type)),
+                })
             }
-            Expression::BinOp(first_expression, operator, second_expression, _) => {
-                let mut first_expression_type_evaluator =
-                    ExpressionTypeEvaluator::new(*first_expression.clone(), self.variables.clone());
+            Expression::BinOp(first_expression, operator, second_expression, _, _) => {
+                let mut first_expression_type_evaluator = ExpressionTypeEvaluator::new(
+                    *first_expression.clone(),
+                    self.variables.clone(),
+                    self.io.clone(),
+                    self.functions.clone(),
+                );
                 let mut second_expression_type_evaluator = ExpressionTypeEvaluator::new(
                     *second_expression.clone(),
                     self.variables.clone(),
+                    self.io.clone(),
+                    self.functions.clone(),
                 );
 
                 match operator {
                     Op::Add => {
-                        let first_expression_type = match first_expression_type_evaluator.parse() {
-                            Ok(val) => val,
-                            Err(e) => return Err(e),
-                        };
-                        let second_expression_type = match second_expression_type_evaluator.parse() {
-                            Ok(val) => val,
-                            Err(e) => return Err(e),
-                        };
+                        let (first_expression_type, second_expression_type) = promote_operands(
+                            first_expression_type_evaluator.parse()?,
+                            second_expression_type_evaluator.parse()?,
+                        );
                         if first_expression_type.expression_type == Type::String
-                        && second_expression_type.expression_type
-                        == Type::String
+                            && second_expression_type.expression_type == Type::String
                         {
-                            if let ExpressionValue::String(first) =
-                            first_expression_type.value
-                            {
+                            if let ExpressionValue::String(first) = first_expression_type.value {
                                 if let ExpressionValue::String(second) =
-                                second_expression_type.value
+                                    second_expression_type.value
                                 {
                                     return Ok(ExpressionResult {
                                         value: ExpressionValue::String(format!(
@@ -428,24 +1191,26 @@ impl ExpressionTypeEvaluator {
                                 expected: "String".to_string(),
                                 found: "Not String".to_string(),
                                 operation: Op::Add,
+                                span: self.expression.span(),
                             });
                         }
 
                         // Ok if both are integers
                         if first_expression_type.expression_type == Type::Integer
-                        && second_expression_type.expression_type
-                        == Type::Integer
+                            && second_expression_type.expression_type == Type::Integer
                         {
-                            if let ExpressionValue::Integer(first) =
-                            first_expression_type.value
-                            {
+                            if let ExpressionValue::Integer(first) = first_expression_type.value {
                                 if let ExpressionValue::Integer(second) =
-                                second_expression_type.value
+                                    second_expression_type.value
                                 {
+                                    let value = first.checked_add(second).ok_or_else(|| {
+                                        AnalysisError::IntegerOverflow {
+                                            operation: Op::Add.to_string(),
+                                            span: self.expression.span(),
+                                        }
+                                    })?;
                                     return Ok(ExpressionResult {
-                                        value: ExpressionValue::Integer(
-                                            first + second,
-                                        ),
+                                        value: ExpressionValue::Integer(value),
                                         expression_type: Type::Integer,
                                     });
                                 }
@@ -454,26 +1219,21 @@ impl ExpressionTypeEvaluator {
 
                         // Ok if both are decimals
                         if first_expression_type.expression_type == Type::Decimal
-                        && second_expression_type.expression_type
-                        == Type::Decimal
+                            && second_expression_type.expression_type == Type::Decimal
                         {
-                            if let ExpressionValue::Decimal(first) =
-                            first_expression_type.value
-                            {
+                            if let ExpressionValue::Decimal(first) = first_expression_type.value {
                                 if let ExpressionValue::Decimal(second) =
-                                second_expression_type.value
+                                    second_expression_type.value
                                 {
                                     return Ok(ExpressionResult {
-                                        value: ExpressionValue::Decimal(
-                                            first + second,
-                                        ),
+                                        value: ExpressionValue::Decimal(first + second),
                                         expression_type: Type::Decimal,
                                     });
                                 }
                             }
                         }
 
-                        return Err(AnalysisError::IllegalOperation {
+                        Err(AnalysisError::IllegalOperation {
                             expected: "Integer, String, Decimal".to_string(),
                             found: format!(
                                 "{} and {}",
@@ -481,33 +1241,31 @@ impl ExpressionTypeEvaluator {
                                 second_expression_type.expression_type
                             ),
                             operation: Op::Add,
-                        });
+                            span: self.expression.span(),
+                        })
                     }
                     Op::Subtract => {
-                        let first_expression_type = match first_expression_type_evaluator.parse() {
-                            Ok(val) => val,
-                            Err(e) => return Err(e),
-                        };
-                        let second_expression_type = match second_expression_type_evaluator.parse() {
-                            Ok(val) => val,
-                            Err(e) => return Err(e),
-                        };
+                        let (first_expression_type, second_expression_type) = promote_operands(
+                            first_expression_type_evaluator.parse()?,
+                            second_expression_type_evaluator.parse()?,
+                        );
 
                         // Only ok if both of them are integers
                         if first_expression_type.expression_type == Type::Integer
-                        && second_expression_type.expression_type
-                        == Type::Integer
+                            && second_expression_type.expression_type == Type::Integer
                         {
-                            if let ExpressionValue::Integer(first) =
-                            first_expression_type.value
-                            {
+                            if let ExpressionValue::Integer(first) = first_expression_type.value {
                                 if let ExpressionValue::Integer(second) =
-                                second_expression_type.value
+                                    second_expression_type.value
                                 {
+                                    let value = first.checked_sub(second).ok_or_else(|| {
+                                        AnalysisError::IntegerOverflow {
+                                            operation: Op::Subtract.to_string(),
+                                            span: self.expression.span(),
+                                        }
+                                    })?;
                                     return Ok(ExpressionResult {
-                                        value: ExpressionValue::Integer(
-                                            first - second,
-                                        ),
+                                        value: ExpressionValue::Integer(value),
                                         expression_type: Type::Integer,
                                     });
                                 }
@@ -516,55 +1274,52 @@ impl ExpressionTypeEvaluator {
 
                         // ok if decimal too
                         if first_expression_type.expression_type == Type::Decimal
-                        && second_expression_type.expression_type
-                        == Type::Decimal
+                            && second_expression_type.expression_type == Type::Decimal
                         {
-                            if let ExpressionValue::Decimal(first) =
-                            first_expression_type.value
-                            {
+                            if let ExpressionValue::Decimal(first) = first_expression_type.value {
                                 if let ExpressionValue::Decimal(second) =
-                                second_expression_type.value
+                                    second_expression_type.value
                                 {
                                     return Ok(ExpressionResult {
-                                        value: ExpressionValue::Decimal(
-                                            first - second,
-                                        ),
+                                        value: ExpressionValue::Decimal(first - second),
                                         expression_type: Type::Decimal,
                                     });
                                 }
                             }
                         }
 
-                        return Err(AnalysisError::IllegalOperation {
-                            expected: "Integer".to_string(),
-                            found: "String".to_string(),
+                        Err(AnalysisError::IllegalOperation {
+                            expected: "Integer, Decimal".to_string(),
+                            found: format!(
+                                "{} and {}",
+                                first_expression_type.expression_type,
+                                second_expression_type.expression_type
+                            ),
                             operation: Op::Subtract,
-                        });
+                            span: self.expression.span(),
+                        })
                     }
                     Op::Multiply => {
-                        let first_expression_type = match first_expression_type_evaluator.parse() {
-                            Ok(val) => val,
-                            Err(e) => return Err(e),
-                        };
-                        let second_expression_type = match second_expression_type_evaluator.parse() {
-                            Ok(val) => val,
-                            Err(e) => return Err(e),
-                        };
+                        let (first_expression_type, second_expression_type) = promote_operands(
+                            first_expression_type_evaluator.parse()?,
+                            second_expression_type_evaluator.parse()?,
+                        );
 
                         if first_expression_type.expression_type == Type::Integer
-                        && second_expression_type.expression_type
-                        == Type::Integer
+                            && second_expression_type.expression_type == Type::Integer
                         {
-                            if let ExpressionValue::Integer(first) =
-                            first_expression_type.value
-                            {
+                            if let ExpressionValue::Integer(first) = first_expression_type.value {
                                 if let ExpressionValue::Integer(second) =
-                                second_expression_type.value
+                                    second_expression_type.value
                                 {
+                                    let value = first.checked_mul(second).ok_or_else(|| {
+                                        AnalysisError::IntegerOverflow {
+                                            operation: Op::Multiply.to_string(),
+                                            span: self.expression.span(),
+                                        }
+                                    })?;
                                     return Ok(ExpressionResult {
-                                        value: ExpressionValue::Integer(
-                                            first * second,
-                                        ),
+                                        value: ExpressionValue::Integer(value),
                                         expression_type: Type::Integer,
                                     });
                                 }
@@ -573,56 +1328,60 @@ impl ExpressionTypeEvaluator {
 
                         // ok if its decimal
                         if first_expression_type.expression_type == Type::Decimal
-                        && second_expression_type.expression_type
-                        == Type::Decimal
+                            && second_expression_type.expression_type == Type::Decimal
                         {
-                            if let ExpressionValue::Decimal(first) =
-                            first_expression_type.value
-                            {
+                            if let ExpressionValue::Decimal(first) = first_expression_type.value {
                                 if let ExpressionValue::Decimal(second) =
-                                second_expression_type.value
+                                    second_expression_type.value
                                 {
                                     return Ok(ExpressionResult {
-                                        value: ExpressionValue::Decimal(
-                                            first * second,
-                                        ),
+                                        value: ExpressionValue::Decimal(first * second),
                                         expression_type: Type::Decimal,
                                     });
                                 }
                             }
                         }
 
-                        return Err(AnalysisError::IllegalOperation {
-                            expected: "Integer".to_string(),
-                            found: "String".to_string(),
+                        Err(AnalysisError::IllegalOperation {
+                            expected: "Integer, Decimal".to_string(),
+                            found: format!(
+                                "{} and {}",
+                                first_expression_type.expression_type,
+                                second_expression_type.expression_type
+                            ),
                             operation: Op::Multiply,
-                        });
+                            span: self.expression.span(),
+                        })
                     }
                     Op::Divide => {
-                        let first_expression_type = match first_expression_type_evaluator.parse() {
-                            Ok(val) => val,
-                            Err(e) => return Err(e),
-                        };
-                        let second_expression_type = match second_expression_type_evaluator.parse() {
-                            Ok(val) => val,
-                            Err(e) => return Err(e),
-                        };
+                        let (first_expression_type, second_expression_type) = promote_operands(
+                            first_expression_type_evaluator.parse()?,
+                            second_expression_type_evaluator.parse()?,
+                        );
 
                         // Only ok if both of them are integers
                         if first_expression_type.expression_type == Type::Integer
-                        && second_expression_type.expression_type
-                        == Type::Integer
+                            && second_expression_type.expression_type == Type::Integer
                         {
-                            if let ExpressionValue::Integer(first) =
-                            first_expression_type.value
-                            {
+                            if let ExpressionValue::Integer(first) = first_expression_type.value {
                                 if let ExpressionValue::Integer(second) =
-                                second_expression_type.value
+                                    second_expression_type.value
                                 {
+                                    if second == 0 {
+                                        return Err(AnalysisError::DivisionByZero {
+                                            span: self.expression.span(),
+                                        });
+                                    }
+                                    // The only quotient that overflows is the smallest integer
+                                    // divided by -1.
+                                    let value = first.checked_div(second).ok_or_else(|| {
+                                        AnalysisError::IntegerOverflow {
+                                            operation: Op::Divide.to_string(),
+                                            span: self.expression.span(),
+                                        }
+                                    })?;
                                     return Ok(ExpressionResult {
-                                        value: ExpressionValue::Integer(
-                                            first / second,
-                                        ),
+                                        value: ExpressionValue::Integer(value),
                                         expression_type: Type::Integer,
                                     });
                                 }
@@ -631,51 +1390,44 @@ impl ExpressionTypeEvaluator {
 
                         // its ok if its decimal too
                         if first_expression_type.expression_type == Type::Decimal
-                        && second_expression_type.expression_type
-                        == Type::Decimal
+                            && second_expression_type.expression_type == Type::Decimal
                         {
-                            if let ExpressionValue::Decimal(first) =
-                            first_expression_type.value
-                            {
+                            if let ExpressionValue::Decimal(first) = first_expression_type.value {
                                 if let ExpressionValue::Decimal(second) =
-                                second_expression_type.value
+                                    second_expression_type.value
                                 {
                                     return Ok(ExpressionResult {
-                                        value: ExpressionValue::Decimal(
-                                            first / second,
-                                        ),
+                                        value: ExpressionValue::Decimal(first / second),
                                         expression_type: Type::Decimal,
                                     });
                                 }
                             }
                         }
 
-                        return Err(AnalysisError::IllegalOperation {
-                            expected: "Integer".to_string(),
-                            found: "String".to_string(),
+                        Err(AnalysisError::IllegalOperation {
+                            expected: "Integer, Decimal".to_string(),
+                            found: format!(
+                                "{} and {}",
+                                first_expression_type.expression_type,
+                                second_expression_type.expression_type
+                            ),
                             operation: Op::Divide,
-                        });
+                            span: self.expression.span(),
+                        })
                     }
                     Op::LessThanEqualTo => {
-                        let first_expression_type = match first_expression_type_evaluator.parse() {
-                            Ok(val) => val,
-                            Err(e) => return Err(e),
-                        };
-                        let second_expression_type = match second_expression_type_evaluator.parse() {
-                            Ok(val) => val,
-                            Err(e) => return Err(e),
-                        };
+                        let (first_expression_type, second_expression_type) = promote_operands(
+                            first_expression_type_evaluator.parse()?,
+                            second_expression_type_evaluator.parse()?,
+                        );
 
                         // Only ok if both of them are integers
                         if first_expression_type.expression_type == Type::Integer
-                        && second_expression_type.expression_type
-                        == Type::Integer
+                            && second_expression_type.expression_type == Type::Integer
                         {
-                            if let ExpressionValue::Integer(first) =
-                            first_expression_type.value
-                            {
+                            if let ExpressionValue::Integer(first) = first_expression_type.value {
                                 if let ExpressionValue::Integer(second) =
-                                second_expression_type.value
+                                    second_expression_type.value
                                 {
                                     let result = first <= second;
                                     return Ok(ExpressionResult {
@@ -686,187 +1438,302 @@ impl ExpressionTypeEvaluator {
                             }
                         }
 
-                        return Err(AnalysisError::IllegalOperation {
-                            expected: "Integer".to_string(),
-                            found: "String".to_string(),
+                        if let (ExpressionValue::Decimal(first), ExpressionValue::Decimal(second)) =
+                            (&first_expression_type.value, &second_expression_type.value)
+                        {
+                            return Ok(ExpressionResult {
+                                value: ExpressionValue::Bool(first <= second),
+                                expression_type: Type::Bool,
+                            });
+                        }
+
+                        Err(AnalysisError::IllegalOperation {
+                            expected: "Integer, Decimal".to_string(),
+                            found: format!(
+                                "{} and {}",
+                                first_expression_type.expression_type,
+                                second_expression_type.expression_type
+                            ),
                             operation: Op::LessThanEqualTo,
-                        });
+                            span: self.expression.span(),
+                        })
                     }
-                    Op::Equals => {
-                        let first_expression_type = match first_expression_type_evaluator.parse() {
-                            Ok(val) => val,
-                            Err(e) => return Err(e),
-                        };
-                        let second_expression_type = match second_expression_type_evaluator.parse() {
-                            Ok(val) => val,
-                            Err(e) => return Err(e),
+                    Op::Equals | Op::NotEquals => {
+                        let (first_expression_type, second_expression_type) = promote_operands(
+                            first_expression_type_evaluator.parse()?,
+                            second_expression_type_evaluator.parse()?,
+                        );
+
+                        match values_are_equal(&first_expression_type, &second_expression_type) {
+                            Some(result) => Ok(ExpressionResult {
+                                value: ExpressionValue::Bool(result == (*operator == Op::Equals)),
+                                expression_type: Type::Bool,
+                            }),
+                            None => Err(AnalysisError::IllegalOperation {
+                                expected: "two values of the same type".to_string(),
+                                found: format!(
+                                    "{} and {}",
+                                    first_expression_type.expression_type,
+                                    second_expression_type.expression_type
+                                ),
+                                operation: operator.clone(),
+                                span: self.expression.span(),
+                            }),
+                        }
+                    }
+                    // The second operand is only evaluated if the first one doesn't decide the
+                    // result on its own.
+                    Op::And | Op::Or => {
+                        let first = first_expression_type_evaluator.parse()?;
+                        let ExpressionValue::Bool(first_value) = first.value else {
+                            return Err(AnalysisError::IllegalOperation {
+                                expected: "Bool".to_string(),
+                                found: first.expression_type.to_string(),
+                                operation: operator.clone(),
+                                span: self.expression.span(),
+                            });
                         };
+                        if first_value == (*operator == Op::Or) {
+                            return Ok(ExpressionResult {
+                                value: ExpressionValue::Bool(first_value),
+                                expression_type: Type::Bool,
+                            });
+                        }
 
-                        // Only ok if both of them are integers
-                        if first_expression_type.expression_type == Type::Integer
-                        && second_expression_type.expression_type
-                        == Type::Integer
-                        {
-                            if let ExpressionValue::Integer(first) =
-                            first_expression_type.value
-                            {
-                                if let ExpressionValue::Integer(second) =
-                                second_expression_type.value
-                                {
-                                    let result = first == second;
-                                    return Ok(ExpressionResult {
-                                        value: ExpressionValue::Bool(result),
-                                        expression_type: Type::Bool,
-                                    });
-                                }
+                        let second = second_expression_type_evaluator.parse()?;
+                        match second.value {
+                            ExpressionValue::Bool(second_value) => Ok(ExpressionResult {
+                                value: ExpressionValue::Bool(second_value),
+                                expression_type: Type::Bool,
+                            }),
+                            _ => Err(AnalysisError::IllegalOperation {
+                                expected: "Bool".to_string(),
+                                found: second.expression_type.to_string(),
+                                operation: operator.clone(),
+                                span: self.expression.span(),
+                            }),
+                        }
+                    }
+                    // Like for `/`, decimals follow IEEE 754, so `1.0 % 0.0` is NaN.
+                    Op::Modulo | Op::Power => {
+                        let (first_expression_type, second_expression_type) = promote_operands(
+                            first_expression_type_evaluator.parse()?,
+                            second_expression_type_evaluator.parse()?,
+                        );
+                        match (&first_expression_type.value, &second_expression_type.value) {
+                            (ExpressionValue::Integer(first), ExpressionValue::Integer(second)) => {
+                                Ok(ExpressionResult {
+                                    value: ExpressionValue::Integer(
+                                        self.integer_operation(operator, *first, *second)?,
+                                    ),
+                                    expression_type: Type::Integer,
+                                })
+                            }
+                            (ExpressionValue::Decimal(first), ExpressionValue::Decimal(second)) => {
+                                let value = if *operator == Op::Modulo {
+                                    first % second
+                                } else {
+                                    first.powf(*second)
+                                };
+                                Ok(ExpressionResult {
+                                    value: ExpressionValue::Decimal(value),
+                                    expression_type: Type::Decimal,
+                                })
+                            }
+                            _ => Err(AnalysisError::IllegalOperation {
+                                expected: "Integer, Decimal".to_string(),
+                                found: format!(
+                                    "{} and {}",
+                                    first_expression_type.expression_type,
+                                    second_expression_type.expression_type
+                                ),
+                                operation: operator.clone(),
+                                span: self.expression.span(),
+                            }),
+                        }
+                    }
+                    Op::BitwiseAnd
+                    | Op::BitwiseOr
+                    | Op::BitwiseXor
+                    | Op::ShiftLeft
+                    | Op::ShiftRight => {
+                        let first_expression_type = first_expression_type_evaluator.parse()?;
+                        let second_expression_type = second_expression_type_evaluator.parse()?;
+                        match (&first_expression_type.value, &second_expression_type.value) {
+                            (ExpressionValue::Integer(first), ExpressionValue::Integer(second)) => {
+                                Ok(ExpressionResult {
+                                    value: ExpressionValue::Integer(
+                                        self.integer_operation(operator, *first, *second)?,
+                                    ),
+                                    expression_type: Type::Integer,
+                                })
                             }
+                            _ => Err(AnalysisError::IllegalOperation {
+                                expected: "Integer".to_string(),
+                                found: format!(
+                                    "{} and {}",
+                                    first_expression_type.expression_type,
+                                    second_expression_type.expression_type
+                                ),
+                                operation: operator.clone(),
+                                span: self.expression.span(),
+                            }),
                         }
-
-                        return Err(AnalysisError::IllegalOperation {
-                            expected: "Integer".to_string(),
-                            found: "String".to_string(),
-                            operation: Op::Equals,
-                        });
                     }
+                    Op::Not | Op::BitwiseNot => Err(AnalysisError::IllegalOperation {
+                        expected: "a binary operator".to_string(),
+                        found: format!("`{}`", operator),
+                        operation: operator.clone(),
+                        span: self.expression.span(),
+                    }),
                     Op::Assign => {
                         // match to drr if its an Identifier
                         match *first_expression.clone() {
-                            Expression::Identifier(identifier_name, _) => {
-                                let first_expression_type = match first_expression_type_evaluator.parse() {
-                                    Ok(val) => val,
-                                    Err(e) => return Err(e),
-                                };
-                                let second_expression_type = match second_expression_type_evaluator.parse() {
-                                    Ok(val) => val,
-                                    Err(e) => return Err(e),
-                                };
+                            Expression::Identifier(identifier_name, _, _) => {
+                                let first_expression_type =
+                                    first_expression_type_evaluator.parse()?;
+                                let second_expression_type =
+                                    second_expression_type_evaluator.parse()?;
 
-                                let mut variables_guard =
-                                self.variables.lock().unwrap();
+                                let mut variables_guard = self.variables.lock().unwrap();
                                 if !variables_guard.contains_key(&identifier_name) {
-                                    drop(variables_guard);
                                     return Err(AnalysisError::UndefinedVariable {
                                         expected: identifier_name.to_string(),
+                                        suggestion: closest_match(
+                                            &identifier_name,
+                                            variables_guard.keys().map(String::as_str),
+                                        ),
+                                        span: self.expression.span(),
                                     });
                                 }
 
                                 // get the variable and return the type
-                                let variable =
-                                variables_guard.get(&identifier_name).unwrap();
+                                let variable = variables_guard.get(&identifier_name).unwrap();
                                 let var_type = variable.variable_type.clone();
-                                if var_type
-                                != second_expression_type.expression_type
-                                {
+                                let Some(new_type) =
+                                    var_type.coerce(&second_expression_type.expression_type)
+                                else {
                                     drop(variables_guard);
                                     return Err(AnalysisError::IllegalOperation {
                                         expected: var_type.to_string(),
-                                        found: second_expression_type
-                                            .expression_type
-                                            .to_string(),
+                                        found: second_expression_type.expression_type.to_string(),
                                         operation: Op::Assign,
+                                        span: self.expression.span(),
                                     });
-                                }
+                                };
 
                                 // Update the value of the variable involved in first expression
-                                let variable = variables_guard
-                                    .get_mut(&identifier_name)
-                                    .unwrap();
+                                let variable = variables_guard.get_mut(&identifier_name).unwrap();
 
-                                variable.value = second_expression_type.value;
+                                variable.value =
+                                    coerce_value(second_expression_type, &new_type).value;
+                                variable.variable_type = new_type;
 
                                 drop(variables_guard);
-                                return Ok(first_expression_type);
+                                Ok(first_expression_type)
                             }
-                            Expression::ArrayAccess(var_name, index_expression ,_ ) => {
+                            Expression::ArrayAccess(var_name, index_expression, _, _) => {
                                 let variables_guard = self.variables.lock().unwrap();
                                 if !variables_guard.contains_key(&var_name) {
-                                    drop(variables_guard);
                                     return Err(AnalysisError::UndefinedVariable {
                                         expected: var_name.to_string(),
+                                        suggestion: closest_match(
+                                            &var_name,
+                                            variables_guard.keys().map(String::as_str),
+                                        ),
+                                        span: self.expression.span(),
                                     });
                                 }
 
                                 drop(variables_guard);
-                                let mut expression_type_evaluator =
-                                ExpressionTypeEvaluator::new(*index_expression.clone(), self.variables.clone());
-                                let index = match expression_type_evaluator.parse() {
-                                    Ok(val) => val,
-                                    Err(e) => return Err(e),
-                                };
+                                let mut expression_type_evaluator = ExpressionTypeEvaluator::new(
+                                    *index_expression.clone(),
+                                    self.variables.clone(),
+                                    self.io.clone(),
+                                    self.functions.clone(),
+                                );
+                                let index = expression_type_evaluator.parse()?;
 
-                                expression_type_evaluator =
-                                    ExpressionTypeEvaluator::new(*second_expression.clone(), self.variables.clone());
-                                let new_value = match expression_type_evaluator.parse() {
-                                    Ok(val) => val,
-                                    Err(e) => return Err(e),
-                                };
+                                expression_type_evaluator = ExpressionTypeEvaluator::new(
+                                    *second_expression.clone(),
+                                    self.variables.clone(),
+                                    self.io.clone(),
+                                    self.functions.clone(),
+                                );
+                                let new_value = expression_type_evaluator.parse()?;
 
                                 let mut variables_guard = self.variables.lock().unwrap();
                                 if let Some(variable) = variables_guard.get_mut(&var_name) {
-                                    if let Type::Array(inner_type) = &variable.variable_type {
-                                        if new_value.expression_type != **inner_type {
+                                    if let Type::Array(inner_type) = variable.variable_type.clone()
+                                    {
+                                        let Some(inner_type) =
+                                            inner_type.coerce(&new_value.expression_type)
+                                        else {
                                             return Err(AnalysisError::MismatchedTypes {
                                                 expected: inner_type.to_string(),
                                                 found: new_value.expression_type.to_string(),
+                                                span: self.expression.span(),
                                             });
-                                        }
+                                        };
+                                        variable.variable_type =
+                                            Type::Array(Box::new(inner_type.clone()));
 
                                         if let ExpressionValue::Array(array) = &mut variable.value {
                                             if let ExpressionValue::Integer(index) = index.value {
                                                 // increase the size of an array because the index
                                                 // can be greater than the size of an array
-                                                if index as usize >= array.len() {
-                                                    for _ in 0..(index as usize - array.len() + 1) {
-                                                        array.push(ExpressionResult {
-                                                            value: ExpressionValue::Integer(0),
-                                                            expression_type: Type::Integer,
-                                                        });
+                                                let index = match usize::try_from(index) {
+                                                    Ok(position) if position < MAX_ARRAY_LENGTH => {
+                                                        position
+                                                    }
+                                                    _ => {
+                                                        return Err(
+                                                            AnalysisError::OutOfBoundsException {
+                                                                expected: array.len().to_string(),
+                                                                found: index.to_string(),
+                                                                span: self.expression.span(),
+                                                            },
+                                                        )
                                                     }
+                                                };
+                                                if index >= array.len() {
+                                                    array.resize_with(index + 1, || {
+                                                        default_value(&inner_type)
+                                                    });
                                                 }
-                                                array[index as usize] = new_value;
+                                                array[index] = coerce_value(new_value, &inner_type);
                                             }
                                         }
                                     }
                                 }
                                 drop(variables_guard);
 
-                                let first_expression_type = match first_expression_type_evaluator.parse() {
-                                    Ok(val) => val,
-                                    Err(e) => return Err(e),
-                                };
+                                let first_expression_type =
+                                    first_expression_type_evaluator.parse()?;
 
-                                return Ok(first_expression_type);
-
-                            } 
-                            _ => {
-                                return Err(AnalysisError::IllegalOperation {
-                                    expected: "Identifier".to_string(),
-                                    found: "Not Identifier".to_string(),
-                                    operation: Op::Assign,
-                                });
+                                Ok(first_expression_type)
                             }
+                            _ => Err(AnalysisError::IllegalOperation {
+                                expected: "Identifier".to_string(),
+                                found: "Not Identifier".to_string(),
+                                operation: Op::Assign,
+                                span: self.expression.span(),
+                            }),
                         }
                     }
                     Op::GreaterThanEqualTo => {
-                        let first_expression_type = match first_expression_type_evaluator.parse() {
-                            Ok(val) => val,
-                            Err(e) => return Err(e),
-                        };
-                        let second_expression_type = match second_expression_type_evaluator.parse() {
-                            Ok(val) => val,
-                            Err(e) => return Err(e),
-                        };
+                        let (first_expression_type, second_expression_type) = promote_operands(
+                            first_expression_type_evaluator.parse()?,
+                            second_expression_type_evaluator.parse()?,
+                        );
 
                         // Only ok if both of them are integers
                         if first_expression_type.expression_type == Type::Integer
-                        && second_expression_type.expression_type
-                        == Type::Integer
+                            && second_expression_type.expression_type == Type::Integer
                         {
-                            if let ExpressionValue::Integer(first) =
-                            first_expression_type.value
-                            {
+                            if let ExpressionValue::Integer(first) = first_expression_type.value {
                                 if let ExpressionValue::Integer(second) =
-                                second_expression_type.value
+                                    second_expression_type.value
                                 {
                                     let result = first >= second;
                                     return Ok(ExpressionResult {
@@ -877,32 +1744,39 @@ impl ExpressionTypeEvaluator {
                             }
                         }
 
-                        return Err(AnalysisError::IllegalOperation {
-                            expected: "Integer".to_string(),
-                            found: "String".to_string(),
+                        if let (ExpressionValue::Decimal(first), ExpressionValue::Decimal(second)) =
+                            (&first_expression_type.value, &second_expression_type.value)
+                        {
+                            return Ok(ExpressionResult {
+                                value: ExpressionValue::Bool(first >= second),
+                                expression_type: Type::Bool,
+                            });
+                        }
+
+                        Err(AnalysisError::IllegalOperation {
+                            expected: "Integer, Decimal".to_string(),
+                            found: format!(
+                                "{} and {}",
+                                first_expression_type.expression_type,
+                                second_expression_type.expression_type
+                            ),
                             operation: Op::GreaterThanEqualTo,
-                        });
+                            span: self.expression.span(),
+                        })
                     }
                     Op::LessThan => {
-                        let first_expression_type = match first_expression_type_evaluator.parse() {
-                            Ok(val) => val,
-                            Err(e) => return Err(e),
-                        };
-                        let second_expression_type = match second_expression_type_evaluator.parse() {
-                            Ok(val) => val,
-                            Err(e) => return Err(e),
-                        };
+                        let (first_expression_type, second_expression_type) = promote_operands(
+                            first_expression_type_evaluator.parse()?,
+                            second_expression_type_evaluator.parse()?,
+                        );
 
                         // Only ok if both of them are integers
                         if first_expression_type.expression_type == Type::Integer
-                        && second_expression_type.expression_type
-                        == Type::Integer
+                            && second_expression_type.expression_type == Type::Integer
                         {
-                            if let ExpressionValue::Integer(first) =
-                            first_expression_type.value
-                            {
+                            if let ExpressionValue::Integer(first) = first_expression_type.value {
                                 if let ExpressionValue::Integer(second) =
-                                second_expression_type.value
+                                    second_expression_type.value
                                 {
                                     let result = first < second;
                                     return Ok(ExpressionResult {
@@ -913,32 +1787,39 @@ impl ExpressionTypeEvaluator {
                             }
                         }
 
-                        return Err(AnalysisError::IllegalOperation {
-                            expected: "Integer".to_string(),
-                            found: "String".to_string(),
+                        if let (ExpressionValue::Decimal(first), ExpressionValue::Decimal(second)) =
+                            (&first_expression_type.value, &second_expression_type.value)
+                        {
+                            return Ok(ExpressionResult {
+                                value: ExpressionValue::Bool(first < second),
+                                expression_type: Type::Bool,
+                            });
+                        }
+
+                        Err(AnalysisError::IllegalOperation {
+                            expected: "Integer, Decimal".to_string(),
+                            found: format!(
+                                "{} and {}",
+                                first_expression_type.expression_type,
+                                second_expression_type.expression_type
+                            ),
                             operation: Op::LessThan,
-                        });
+                            span: self.expression.span(),
+                        })
                     }
                     Op::GreaterThan => {
-                        let first_expression_type = match first_expression_type_evaluator.parse() {
-                            Ok(val) => val,
-                            Err(e) => return Err(e),
-                        };
-                        let second_expression_type = match second_expression_type_evaluator.parse() {
-                            Ok(val) => val,
-                            Err(e) => return Err(e),
-                        };
+                        let (first_expression_type, second_expression_type) = promote_operands(
+                            first_expression_type_evaluator.parse()?,
+                            second_expression_type_evaluator.parse()?,
+                        );
 
                         // Only ok if both of them are integers
                         if first_expression_type.expression_type == Type::Integer
-                        && second_expression_type.expression_type
-                        == Type::Integer
+                            && second_expression_type.expression_type == Type::Integer
                         {
-                            if let ExpressionValue::Integer(first) =
-                            first_expression_type.value
-                            {
+                            if let ExpressionValue::Integer(first) = first_expression_type.value {
                                 if let ExpressionValue::Integer(second) =
-                                second_expression_type.value
+                                    second_expression_type.value
                                 {
                                     let result = first > second;
                                     return Ok(ExpressionResult {
@@ -949,21 +1830,38 @@ impl ExpressionTypeEvaluator {
                             }
                         }
 
-                        return Err(AnalysisError::IllegalOperation {
-                            expected: "Integer".to_string(),
-                            found: "String".to_string(),
+                        if let (ExpressionValue::Decimal(first), ExpressionValue::Decimal(second)) =
+                            (&first_expression_type.value, &second_expression_type.value)
+                        {
+                            return Ok(ExpressionResult {
+                                value: ExpressionValue::Bool(first > second),
+                                expression_type: Type::Bool,
+                            });
+                        }
+
+                        Err(AnalysisError::IllegalOperation {
+                            expected: "Integer, Decimal".to_string(),
+                            found: format!(
+                                "{} and {}",
+                                first_expression_type.expression_type,
+                                second_expression_type.expression_type
+                            ),
                             operation: Op::GreaterThan,
-                        });
+                            span: self.expression.span(),
+                        })
                     }
                 }
-
             }
-            Expression::Identifier(identifier_name, _) => {
+            Expression::Identifier(identifier_name, _, _) => {
                 let variables_guard = self.variables.lock().unwrap();
                 if !variables_guard.contains_key(identifier_name) {
-                    drop(variables_guard);
                     return Err(AnalysisError::UndefinedVariable {
                         expected: identifier_name.to_string(),
+                        suggestion: closest_match(
+                            identifier_name,
+                            variables_guard.keys().map(String::as_str),
+                        ),
+                        span: self.expression.span(),
                     });
                 }
 
@@ -972,12 +1870,17 @@ impl ExpressionTypeEvaluator {
                 let var_type = variable.variable_type.clone();
                 let var_value = variable.value.clone();
                 drop(variables_guard);
-                return Ok(ExpressionResult {
+                Ok(ExpressionResult {
                     value: var_value,
                     expression_type: var_type,
-                });
+                })
             }
-            Expression::FunctionCall(function_name, params, _) => {
+            Expression::FunctionCall(function_name, params, _, _) => {
+                let function = self.functions.lock().unwrap().get(function_name).cloned();
+                if let Some(function) = function {
+                    return self.call_function(function_name, function, params);
+                }
+
                 let native_functions = load_native_functions();
                 if native_functions.contains_key(function_name) {
                     let native_function = native_functions.get(function_name).unwrap();
@@ -986,6 +1889,7 @@ impl ExpressionTypeEvaluator {
                         return Err(AnalysisError::ArgumentCountMismatch {
                             expected: native_function.parameters_types.len().to_string(),
                             found: params.len().to_string(),
+                            span: self.expression.span(),
                         });
                     }
 
@@ -996,8 +1900,12 @@ impl ExpressionTypeEvaluator {
                     for param in params {
                         // print the  param
                         // param value
-                        let mut expression_type_evaluator =
-                            ExpressionTypeEvaluator::new(param.clone(), self.variables.clone());
+                        let mut expression_type_evaluator = ExpressionTypeEvaluator::new(
+                            param.clone(),
+                            self.variables.clone(),
+                            self.io.clone(),
+                            self.functions.clone(),
+                        );
                         match expression_type_evaluator.parse() {
                             Ok(expression_type) => {
                                 parameters.push(expression_type);
@@ -1006,28 +1914,28 @@ impl ExpressionTypeEvaluator {
                         }
                     }
 
-                    // lets make sure all the parameters are of the correct type
+                    // lets make sure all the parameters are of the correct type. An integer
+                    // passed for a decimal is left as it is, the native functions taking
+                    // decimals accept both.
                     for (i, param) in parameters.iter().enumerate() {
-                        if param.expression_type != native_function.parameters_types[i] {
-                            // We need this to match the boxed parameters
-                            match param.expression_type {
-                                Type::Array(_) => {
-                                    if let Type::Array(_) = native_function.parameters_types[i] {
-                                        continue;
-                                    }
-                                }
-                                _ => {}
-                            }
+                        if native_function.parameters_types[i]
+                            .coerce(&param.expression_type)
+                            .is_none()
+                        {
                             return Err(AnalysisError::ArgumentTypeMismatch {
                                 argument_name: i.to_string(),
                                 expected: native_function.parameters_types[i].to_string(),
                                 found: param.expression_type.to_string(),
+                                span: params[i].span(),
                             });
                         }
                     }
 
                     // if everything is ok, then lets start executing the function
-                    let mut function_executor = FunctionExecutor {};
+                    let mut function_executor = FunctionExecutor {
+                        io: self.io.clone(),
+                        span: self.expression.span(),
+                    };
                     match function_executor.execute(function_name.to_string(), parameters) {
                         Ok(result) => {
                             return Ok(result);
@@ -1036,50 +1944,105 @@ impl ExpressionTypeEvaluator {
                     }
                 }
 
-                return Err(AnalysisError::UndefinedFunction {
+                let functions_guard = self.functions.lock().unwrap();
+                let known_functions = functions_guard
+                    .keys()
+                    .chain(native_functions.keys())
+                    .map(String::as_str);
+                Err(AnalysisError::UndefinedFunction {
                     expected: function_name.to_string(),
                     found: function_name.to_string(),
-                });
+                    suggestion: closest_match(function_name, known_functions),
+                    span: self.expression.span(),
+                })
             }
-            Expression::UnaryOp(operator, expr, _) => {
-                let mut expression_type_evaluator =
-                    ExpressionTypeEvaluator::new(*expr.clone(), self.variables.clone());
+            Expression::UnaryOp(operator, expr, _, _) => {
+                let mut expression_type_evaluator = ExpressionTypeEvaluator::new(
+                    *expr.clone(),
+                    self.variables.clone(),
+                    self.io.clone(),
+                    self.functions.clone(),
+                );
                 match expression_type_evaluator.parse() {
                     Ok(expression_type) => match operator {
-                        Op::Subtract => {
-                            if expression_type.expression_type == Type::Integer {
-                                if let ExpressionValue::Integer(value) = expression_type.value {
-                                    return Ok(ExpressionResult {
-                                        value: ExpressionValue::Integer(-value),
-                                        expression_type: Type::Integer,
-                                    });
-                                }
+                        // Unary `+` leaves a number as it is.
+                        Op::Add => match expression_type.value {
+                            ExpressionValue::Integer(_) | ExpressionValue::Decimal(_) => {
+                                Ok(expression_type)
                             }
-
-                            return Err(AnalysisError::IllegalOperation {
-                                expected: "Integer".to_string(),
-                                found: "String".to_string(),
+                            _ => Err(AnalysisError::IllegalOperation {
+                                expected: "Integer, Decimal".to_string(),
+                                found: expression_type.expression_type.to_string(),
+                                operation: Op::Add,
+                                span: self.expression.span(),
+                            }),
+                        },
+                        Op::Subtract => match expression_type.value {
+                            ExpressionValue::Integer(value) => Ok(ExpressionResult {
+                                value: ExpressionValue::Integer(value.checked_neg().ok_or_else(
+                                    || AnalysisError::IntegerOverflow {
+                                        operation: Op::Subtract.to_string(),
+                                        span: self.expression.span(),
+                                    },
+                                )?),
+                                expression_type: Type::Integer,
+                            }),
+                            ExpressionValue::Decimal(value) => Ok(ExpressionResult {
+                                value: ExpressionValue::Decimal(-value),
+                                expression_type: Type::Decimal,
+                            }),
+                            _ => Err(AnalysisError::IllegalOperation {
+                                expected: "Integer, Decimal".to_string(),
+                                found: expression_type.expression_type.to_string(),
                                 operation: Op::Subtract,
-                            });
-                        }
-                        _ => {
-                            return Err(AnalysisError::IllegalOperation {
+                                span: self.expression.span(),
+                            }),
+                        },
+                        Op::Not => match expression_type.value {
+                            ExpressionValue::Bool(value) => Ok(ExpressionResult {
+                                value: ExpressionValue::Bool(!value),
+                                expression_type: Type::Bool,
+                            }),
+                            _ => Err(AnalysisError::IllegalOperation {
+                                expected: "Bool".to_string(),
+                                found: expression_type.expression_type.to_string(),
+                                operation: Op::Not,
+                                span: self.expression.span(),
+                            }),
+                        },
+                        Op::BitwiseNot => match expression_type.value {
+                            ExpressionValue::Integer(value) => Ok(ExpressionResult {
+                                value: ExpressionValue::Integer(!value),
+                                expression_type: Type::Integer,
+                            }),
+                            _ => Err(AnalysisError::IllegalOperation {
                                 expected: "Integer".to_string(),
-                                found: "String".to_string(),
-                                operation: operator.clone(),
-                            });
-                        }
+                                found: expression_type.expression_type.to_string(),
+                                operation: Op::BitwiseNot,
+                                span: self.expression.span(),
+                            }),
+                        },
+                        _ => Err(AnalysisError::IllegalOperation {
+                            expected: "Integer".to_string(),
+                            found: "String".to_string(),
+                            operation: operator.clone(),
+                            span: self.expression.span(),
+                        }),
                     },
-                    Err(e) => return Err(e),
+                    Err(e) => Err(e),
                 }
             }
-            Expression::ArrayAccess(identifer, expression, _) => {
+            Expression::ArrayAccess(identifer, expression, _, _) => {
                 // identifier is now string, so check in the variables
                 let variables_guard = self.variables.lock().unwrap();
                 if !variables_guard.contains_key(identifer) {
-                    drop(variables_guard);
                     return Err(AnalysisError::UndefinedVariable {
                         expected: identifer.to_string(),
+                        suggestion: closest_match(
+                            identifer,
+                            variables_guard.keys().map(String::as_str),
+                        ),
+                        span: self.expression.span(),
                     });
                 }
 
@@ -1090,16 +2053,21 @@ impl ExpressionTypeEvaluator {
                 drop(variables_guard);
 
                 // check if the type is array
-                if let Type::Array(inner_type) = &var_type {
+                if let Type::Array(_) = &var_type {
                     // check if the expression is integer
-                    let mut expression_type_evaluator =
-                        ExpressionTypeEvaluator::new(*expression.clone(), self.variables.clone());
+                    let mut expression_type_evaluator = ExpressionTypeEvaluator::new(
+                        *expression.clone(),
+                        self.variables.clone(),
+                        self.io.clone(),
+                        self.functions.clone(),
+                    );
                     match expression_type_evaluator.parse() {
                         Ok(expression_type) => {
                             if expression_type.expression_type != Type::Integer {
                                 return Err(AnalysisError::MismatchedTypes {
                                     expected: Type::Integer.to_string(),
                                     found: expression_type.expression_type.to_string(),
+                                    span: self.expression.span(),
                                 });
                             }
 
@@ -1110,137 +2078,69 @@ impl ExpressionTypeEvaluator {
                                         return Err(AnalysisError::OutOfBoundsException {
                                             expected: array.len().to_string(),
                                             found: index.to_string(),
+                                            span: self.expression.span(),
                                         });
                                     }
 
-                                    match **inner_type {
-                                        Type::Integer => {
-                                            if let ExpressionValue::Integer(value) =
-                                                array[index as usize].value
-                                            {
-                                                return Ok(ExpressionResult {
-                                                    value: ExpressionValue::Integer(value),
-                                                    expression_type: Type::Integer,
-                                                });
-                                            }
-                                            return Err(AnalysisError::MismatchedTypes {
-                                                expected: Type::Integer.to_string(),
-                                                found: array[index as usize]
-                                                    .expression_type
-                                                    .to_string(),
-                                            });
-                                        }
-                                        Type::Decimal => {
-                                            if let ExpressionValue::Decimal(value) =
-                                                array[index as usize].value
-                                            {
-                                                return Ok(ExpressionResult {
-                                                    value: ExpressionValue::Decimal(value),
-                                                    expression_type: Type::Decimal,
-                                                });
-                                            }
-                                            return Err(AnalysisError::MismatchedTypes {
-                                                expected: Type::Decimal.to_string(),
-                                                found: array[index as usize]
-                                                    .expression_type
-                                                    .to_string(),
-                                            });
-                                        }
-                                        Type::String => {
-                                            if let ExpressionValue::String(value) =
-                                                &array[index as usize].value
-                                            {
-                                                return Ok(ExpressionResult {
-                                                    value: ExpressionValue::String(
-                                                        value.to_string(),
-                                                    ),
-                                                    expression_type: Type::String,
-                                                });
-                                            }
-
-                                            return Err(AnalysisError::MismatchedTypes {
-                                                expected: Type::String.to_string(),
-                                                found: array[index as usize]
-                                                    .expression_type
-                                                    .to_string(),
-                                            });
-                                        }
-                                        Type::Bool => {
-                                            if let ExpressionValue::Bool(value) =
-                                                array[index as usize].value
-                                            {
-                                                return Ok(ExpressionResult {
-                                                    value: ExpressionValue::Bool(value),
-                                                    expression_type: Type::Bool,
-                                                });
-                                            }
-
-                                            return Err(AnalysisError::MismatchedTypes {
-                                                expected: Type::Bool.to_string(),
-                                                found: array[index as usize]
-                                                    .expression_type
-                                                    .to_string(),
-                                            });
-                                        }
-                                        Type::Array(_) => {
-                                            if let ExpressionValue::Array(value) =
-                                                &array[index as usize].value
-                                            {
-                                                return Ok(ExpressionResult {
-                                                    value: ExpressionValue::Array(value.to_vec()),
-                                                    expression_type: Type::Array(Box::new(
-                                                        Type::Integer,
-                                                    )),
-                                                });
-                                            }
-                                            return Err(AnalysisError::MismatchedTypes {
-                                                expected: Type::Array(Box::new(Type::Integer))
-                                                    .to_string(),
-                                                found: array[index as usize]
-                                                    .expression_type
-                                                    .to_string(),
-                                            });
-                                        }
-                                        Type::Any => {
-                                            return Ok(ExpressionResult {
-                                                value: ExpressionValue::String("".to_string()),
-                                                expression_type: Type::String,
-                                            });
-                                        }
-                                        Type::Void => {
-                                            return Ok(ExpressionResult {
-                                                value: ExpressionValue::String("".to_string()),
-                                                expression_type: Type::String,
-                                            });
-                                        }
-                                    }
+                                    // Every element knows its own type, which may be more precise than the
+                                    // element type of the variable, e.g. for an array of arrays.
+                                    return Ok(array[index as usize].clone());
                                 }
 
                                 return Err(AnalysisError::MismatchedTypes {
-                                    expected: Type::Array(Box::new(Type::Integer)).to_string(),
+                                    expected: Type::Array(Box::new(Type::Any)).to_string(),
                                     found: var_type.to_string(),
+                                    span: self.expression.span(),
                                 });
                             }
                             return Err(AnalysisError::MismatchedTypes {
                                 expected: Type::Integer.to_string(),
                                 found: expression_type.expression_type.to_string(),
+                                span: self.expression.span(),
                             });
                         }
                         Err(e) => return Err(e),
                     }
                 }
-                return Err(AnalysisError::MismatchedTypes {
-                    expected: Type::Array(Box::new(Type::Integer)).to_string(),
+                Err(AnalysisError::MismatchedTypes {
+                    expected: Type::Array(Box::new(Type::Any)).to_string(),
                     found: var_type.to_string(),
-                });
+                    span: self.expression.span(),
+                })
             }
         }
     }
 }
 
-pub struct FunctionExecutor {}
+pub struct FunctionExecutor {
+    io: Arc<Mutex<dyn HostIo>>,
+    // Where the function was called from, for errors.
+    span: Span,
+}
 
 impl FunctionExecutor {
+    fn write(&self, output: &str) -> Result<(), AnalysisError> {
+        self.io
+            .lock()
+            .unwrap()
+            .write(output)
+            .map_err(|e| AnalysisError::IoError {
+                message: e.to_string(),
+                span: self.span,
+            })
+    }
+
+    fn read_line(&self) -> Result<String, AnalysisError> {
+        self.io
+            .lock()
+            .unwrap()
+            .read_line()
+            .map_err(|e| AnalysisError::IoError {
+                message: e.to_string(),
+                span: self.span,
+            })
+    }
+
     fn execute(
         &mut self,
         function_name: String,
@@ -1254,6 +2154,7 @@ impl FunctionExecutor {
                 return Err(AnalysisError::ArgumentCountMismatch {
                     expected: native_function.parameters_types.len().to_string(),
                     found: params.len().to_string(),
+                    span: self.span,
                 });
             }
 
@@ -1262,7 +2163,7 @@ impl FunctionExecutor {
                 FunctionModule::IO => match function_name.as_str() {
                     "print" => {
                         if let ExpressionValue::String(value) = &params[0].value {
-                            print!("{}", value);
+                            self.write(value)?;
                         }
                         return Ok(ExpressionResult {
                             value: ExpressionValue::String("".to_string()),
@@ -1271,7 +2172,7 @@ impl FunctionExecutor {
                     }
                     "println" => {
                         if let ExpressionValue::String(value) = &params[0].value {
-                            println!("{}", value);
+                            self.write(&format!("{}\n", value))?;
                         }
                         return Ok(ExpressionResult {
                             value: ExpressionValue::String("".to_string()),
@@ -1279,8 +2180,7 @@ impl FunctionExecutor {
                         });
                     }
                     "input" => {
-                        let mut input = String::new();
-                        std::io::stdin().read_line(&mut input).unwrap();
+                        let input = self.read_line()?;
                         return Ok(ExpressionResult {
                             value: ExpressionValue::String(input),
                             expression_type: return_type,
@@ -1288,7 +2188,7 @@ impl FunctionExecutor {
                     }
                     "exit" => {
                         std::process::exit(match params[0].value {
-                            ExpressionValue::Integer(value) => value,
+                            ExpressionValue::Integer(value) => value as i32,
                             _ => 0,
                         });
                     }
@@ -1296,6 +2196,8 @@ impl FunctionExecutor {
                         return Err(AnalysisError::UndefinedFunction {
                             expected: function_name.to_string(),
                             found: function_name.to_string(),
+                            suggestion: None,
+                            span: self.span,
                         });
                     }
                 },
@@ -1305,7 +2207,13 @@ impl FunctionExecutor {
                             // sanitize the input
                             if let ExpressionValue::String(value) = &params[0].value {
                                 let sanitized_input = value.trim();
-                                let result = sanitized_input.parse::<i32>().unwrap();
+                                let Ok(result) = sanitized_input.parse::<i64>() else {
+                                    return Err(AnalysisError::InvalidNumber {
+                                        expected: Type::Integer.to_string(),
+                                        found: value.to_string(),
+                                        span: self.span,
+                                    });
+                                };
                                 return Ok(ExpressionResult {
                                     value: ExpressionValue::Integer(result),
                                     expression_type: return_type,
@@ -1316,7 +2224,13 @@ impl FunctionExecutor {
                             // sanitize the input
                             if let ExpressionValue::String(value) = &params[0].value {
                                 let sanitized_input = value.trim();
-                                let result = sanitized_input.parse::<f32>().unwrap();
+                                let Ok(result) = sanitized_input.parse::<f64>() else {
+                                    return Err(AnalysisError::InvalidNumber {
+                                        expected: Type::Decimal.to_string(),
+                                        found: value.to_string(),
+                                        span: self.span,
+                                    });
+                                };
                                 return Ok(ExpressionResult {
                                     value: ExpressionValue::Decimal(result),
                                     expression_type: return_type,
@@ -1327,7 +2241,7 @@ impl FunctionExecutor {
                             if let ExpressionValue::String(value) = &params[0].value {
                                 let result = value.len();
                                 return Ok(ExpressionResult {
-                                    value: ExpressionValue::Integer(result as i32),
+                                    value: ExpressionValue::Integer(result as i64),
                                     expression_type: return_type,
                                 });
                             }
@@ -1336,6 +2250,8 @@ impl FunctionExecutor {
                             return Err(AnalysisError::UndefinedFunction {
                                 expected: function_name.to_string(),
                                 found: function_name.to_string(),
+                                suggestion: None,
+                                span: self.span,
                             });
                         }
                     }
@@ -1350,9 +2266,75 @@ impl FunctionExecutor {
                         }
                     }
                     "floattostr" => {
-                        if let ExpressionValue::Decimal(value) = params[0].value {
+                        let value = match params[0].value {
+                            ExpressionValue::Integer(value) => value as f64,
+                            ExpressionValue::Decimal(value) => value,
+                            _ => 0.0,
+                        };
+                        return Ok(ExpressionResult {
+                            value: ExpressionValue::String(value.to_string()),
+                            expression_type: return_type,
+                        });
+                    }
+                    // Drops the fractional part, rounding toward zero.
+                    "int" => match params[0].value {
+                        ExpressionValue::Integer(value) => {
                             return Ok(ExpressionResult {
-                                value: ExpressionValue::String(value.to_string()),
+                                value: ExpressionValue::Integer(value),
+                                expression_type: return_type,
+                            });
+                        }
+                        ExpressionValue::Decimal(value) => {
+                            // i64::MAX as f64 rounds up to 2^63, which is already out of range.
+                            let value = value.trunc();
+                            if !(value >= i64::MIN as f64 && value < i64::MAX as f64) {
+                                return Err(AnalysisError::IntegerOverflow {
+                                    operation: function_name,
+                                    span: self.span,
+                                });
+                            }
+                            return Ok(ExpressionResult {
+                                value: ExpressionValue::Integer(value as i64),
+                                expression_type: return_type,
+                            });
+                        }
+                        _ => {}
+                    },
+                    "float" => match params[0].value {
+                        ExpressionValue::Integer(value) => {
+                            return Ok(ExpressionResult {
+                                value: ExpressionValue::Decimal(value as f64),
+                                expression_type: return_type,
+                            });
+                        }
+                        ExpressionValue::Decimal(value) => {
+                            return Ok(ExpressionResult {
+                                value: ExpressionValue::Decimal(value),
+                                expression_type: return_type,
+                            });
+                        }
+                        _ => {}
+                    },
+                    name if integer_builtin_kind(name).is_some() => {
+                        if let (ExpressionValue::Integer(first), ExpressionValue::Integer(second)) =
+                            (&params[0].value, &params[1].value)
+                        {
+                            let value =
+                                match (integer_builtin(name, *first, *second), params.get(2)) {
+                                    (Some(value), _) => value,
+                                    (None, Some(fallback)) => match fallback.value {
+                                        ExpressionValue::Integer(fallback) => fallback,
+                                        _ => 0,
+                                    },
+                                    // Without a fallback, the only failure is dividing by zero.
+                                    (None, None) => {
+                                        return Err(AnalysisError::DivisionByZero {
+                                            span: self.span,
+                                        })
+                                    }
+                                };
+                            return Ok(ExpressionResult {
+                                value: ExpressionValue::Integer(value),
                                 expression_type: return_type,
                             });
                         }
@@ -1361,6 +2343,8 @@ impl FunctionExecutor {
                         return Err(AnalysisError::UndefinedFunction {
                             expected: function_name.to_string(),
                             found: function_name.to_string(),
+                            suggestion: None,
+                            span: self.span,
                         });
                     }
                 },
@@ -1368,7 +2352,7 @@ impl FunctionExecutor {
                     "array_length" => {
                         if let ExpressionValue::Array(value) = &params[0].value {
                             return Ok(ExpressionResult {
-                                value: ExpressionValue::Integer(value.len() as i32),
+                                value: ExpressionValue::Integer(value.len() as i64),
                                 expression_type: return_type,
                             });
                         }
@@ -1377,16 +2361,54 @@ impl FunctionExecutor {
                         return Err(AnalysisError::UndefinedFunction {
                             expected: function_name.to_string(),
                             found: function_name.to_string(),
+                            suggestion: None,
+                            span: self.span,
                         });
                     }
                 },
             }
         }
 
-        return Err(AnalysisError::UndefinedFunction {
+        Err(AnalysisError::UndefinedFunction {
             expected: function_name.to_string(),
             found: function_name.to_string(),
-        });
+            suggestion: None,
+            span: self.span,
+        })
+    }
+}
+
+// Splits the name of an integer arithmetic builtin like `wrapping_add` into how it handles
+// overflow and the operation, or None if name isn't one.
+fn integer_builtin_kind(name: &str) -> Option<(&str, &str)> {
+    let (kind, operation) = name.split_once('_')?;
+    let is_kind = matches!(kind, "wrapping" | "saturating" | "checked");
+    let is_operation = matches!(operation, "add" | "sub" | "mul" | "div");
+    (is_kind && is_operation).then_some((kind, operation))
+}
+
+// Runs an integer arithmetic builtin. wrapping_* wraps around on overflow, saturating_* stops at
+// the smallest or largest integer, and checked_* gives None so that the caller can fall back to
+// its third argument. Dividing by zero gives None whatever the kind.
+fn integer_builtin(name: &str, first: i64, second: i64) -> Option<i64> {
+    let (kind, operation) = integer_builtin_kind(name)?;
+    if operation == "div" && second == 0 {
+        return None;
+    }
+    match (kind, operation) {
+        ("wrapping", "add") => Some(first.wrapping_add(second)),
+        ("wrapping", "sub") => Some(first.wrapping_sub(second)),
+        ("wrapping", "mul") => Some(first.wrapping_mul(second)),
+        ("wrapping", "div") => Some(first.wrapping_div(second)),
+        ("saturating", "add") => Some(first.saturating_add(second)),
+        ("saturating", "sub") => Some(first.saturating_sub(second)),
+        ("saturating", "mul") => Some(first.saturating_mul(second)),
+        ("saturating", "div") => Some(first.saturating_div(second)),
+        ("checked", "add") => first.checked_add(second),
+        ("checked", "sub") => first.checked_sub(second),
+        ("checked", "mul") => first.checked_mul(second),
+        ("checked", "div") => first.checked_div(second),
+        _ => None,
     }
 }
 
@@ -1404,14 +2426,14 @@ pub enum FunctionModule {
     Array,
 }
 
-fn load_native_functions() -> HashMap<String, FunctionDefination> {
+pub(crate) fn load_native_functions() -> HashMap<String, FunctionDefination> {
     let mut native_functions = HashMap::new();
     native_functions.insert(
         "print".to_string(),
         FunctionDefination {
             name: "print".to_string(),
             parameters_types: vec![Type::String],
-            return_type: Type::String,
+            return_type: Type::Void,
             module: FunctionModule::IO,
         },
     );
@@ -1421,7 +2443,7 @@ fn load_native_functions() -> HashMap<String, FunctionDefination> {
         FunctionDefination {
             name: "println".to_string(),
             parameters_types: vec![Type::String],
-            return_type: Type::String,
+            return_type: Type::Void,
             module: FunctionModule::IO,
         },
     );
@@ -1486,12 +2508,52 @@ fn load_native_functions() -> HashMap<String, FunctionDefination> {
         },
     );
 
+    // int and float take an Integer or a Decimal, which the argument checks allow for a Decimal
+    // parameter.
+    native_functions.insert(
+        "int".to_string(),
+        FunctionDefination {
+            name: "int".to_string(),
+            parameters_types: vec![Type::Decimal],
+            return_type: Type::Integer,
+            module: FunctionModule::Math,
+        },
+    );
+
+    native_functions.insert(
+        "float".to_string(),
+        FunctionDefination {
+            name: "float".to_string(),
+            parameters_types: vec![Type::Decimal],
+            return_type: Type::Decimal,
+            module: FunctionModule::Math,
+        },
+    );
+
+    // wrapping_add(a, b), saturating_add(a, b) and checked_add(a, b, fallback), and the same for
+    // sub, mul and div.
+    for operation in ["add", "sub", "mul", "div"] {
+        for kind in ["wrapping", "saturating", "checked"] {
+            let name = format!("{}_{}", kind, operation);
+            let parameter_count = if kind == "checked" { 3 } else { 2 };
+            native_functions.insert(
+                name.clone(),
+                FunctionDefination {
+                    name,
+                    parameters_types: vec![Type::Integer; parameter_count],
+                    return_type: Type::Integer,
+                    module: FunctionModule::Math,
+                },
+            );
+        }
+    }
+
     native_functions.insert(
         "exit".to_string(),
         FunctionDefination {
             name: "exit".to_string(),
             parameters_types: vec![Type::Integer],
-            return_type: Type::String,
+            return_type: Type::Void,
             module: FunctionModule::IO,
         },
     );
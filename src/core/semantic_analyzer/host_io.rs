@@ -0,0 +1,55 @@
+use std::io::{self, BufRead, Write};
+
+// Where `print`, `println` and `input` send and read their data. Hosts that embed the language
+// can supply their own implementation to capture the output or feed scripted input. It has to be
+// Send so that an interpreter can be moved onto another thread, e.g. a worker of a server.
+pub trait HostIo: Send {
+    fn write(&mut self, output: &str) -> io::Result<()>;
+
+    // Reads a single line, including the trailing newline. Returns an empty string once the
+    // input has been exhausted.
+    fn read_line(&mut self) -> io::Result<String>;
+}
+
+// The real terminal, used unless the host asks for something else.
+pub struct StdIo;
+
+impl HostIo for StdIo {
+    fn write(&mut self, output: &str) -> io::Result<()> {
+        let mut stdout = io::stdout();
+        stdout.write_all(output.as_bytes())?;
+        stdout.flush()
+    }
+
+    fn read_line(&mut self) -> io::Result<String> {
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        Ok(input)
+    }
+}
+
+// Adapts any reader and writer pair, e.g. a `Cursor` with scripted input and a `Vec<u8>` that
+// collects the output, or both halves of a socket.
+pub struct StreamIo<R: BufRead, W: Write> {
+    pub reader: R,
+    pub writer: W,
+}
+
+impl<R: BufRead, W: Write> StreamIo<R, W> {
+    pub fn new(reader: R, writer: W) -> Self {
+        StreamIo { reader, writer }
+    }
+}
+
+impl<R: BufRead + Send, W: Write + Send> HostIo for StreamIo<R, W> {
+    fn write(&mut self, output: &str) -> io::Result<()> {
+        self.writer.write_all(output.as_bytes())?;
+        self.writer.flush()
+    }
+
+    fn read_line(&mut self) -> io::Result<String> {
+        let mut input = String::new();
+        self.reader.read_line(&mut input)?;
+        Ok(input)
+    }
+}
@@ -1,3 +1,7 @@
 pub mod defination;
+pub mod host_io;
+pub mod scopes;
+pub mod type_checker;
 
 pub use defination::Analyzer;
+pub use type_checker::TypeChecker;
@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+
+use crate::core::semantic_analyzer::defination::Variable;
+
+// The variables visible at some point of the program, one map per enclosing block with the
+// innermost last. A block may declare a variable that shadows one of an enclosing block, and its
+// variables are dropped once it ends.
+//
+// A function body sees its own blocks and the global scope, but not the blocks of whoever called
+// it, so `frames` remembers where the scopes of every function being run start.
+pub struct Scopes {
+    scopes: Vec<HashMap<String, Variable>>,
+    frames: Vec<usize>,
+}
+
+impl Default for Scopes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scopes {
+    // Just the global scope.
+    pub fn new() -> Self {
+        Scopes {
+            scopes: vec![HashMap::new()],
+            frames: vec![],
+        }
+    }
+
+    pub fn push(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    // The global scope is never dropped, and neither is the outermost scope of a function before
+    // the function ends.
+    pub fn pop(&mut self) {
+        if self.scopes.len() > self.first_local_scope() + 1 {
+            self.scopes.pop();
+        }
+    }
+
+    // Starts running the body of a function, with a scope of its own for the parameters.
+    pub fn push_function(&mut self) {
+        self.frames.push(self.scopes.len());
+        self.scopes.push(HashMap::new());
+    }
+
+    // Drops every scope of the innermost function, however deep in its blocks it returned.
+    pub fn pop_function(&mut self) {
+        if let Some(start) = self.frames.pop() {
+            self.scopes.truncate(start);
+        }
+    }
+
+    // How many function calls are being run.
+    pub fn call_depth(&self) -> usize {
+        self.frames.len()
+    }
+
+    // Declares a variable in the innermost block.
+    pub fn insert(&mut self, name: String, variable: Variable) {
+        self.scopes.last_mut().unwrap().insert(name, variable);
+    }
+
+    // Whether the innermost block already declares name, so that declaring it again is an error.
+    pub fn is_declared_here(&self, name: &str) -> bool {
+        self.scopes.last().unwrap().contains_key(name)
+    }
+
+    pub fn contains_key(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    // The innermost variable called name.
+    pub fn get(&self, name: &str) -> Option<&Variable> {
+        self.visible()
+            .rev()
+            .find_map(|index| self.scopes[index].get(name))
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Variable> {
+        let index = self
+            .visible()
+            .rev()
+            .find(|index| self.scopes[*index].contains_key(name))?;
+        self.scopes[index].get_mut(name)
+    }
+
+    // The names of every visible variable.
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.visible().flat_map(|index| self.scopes[index].keys())
+    }
+
+    // Every visible variable, outermost first, so that a shadowing variable comes after the one
+    // it shadows.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Variable)> {
+        self.visible().flat_map(|index| self.scopes[index].iter())
+    }
+
+    fn first_local_scope(&self) -> usize {
+        self.frames.last().copied().unwrap_or(0)
+    }
+
+    // The indexes of the visible scopes, outermost first: the global scope, then the scopes of
+    // the function being run.
+    fn visible(&self) -> impl DoubleEndedIterator<Item = usize> {
+        let start = self.first_local_scope();
+        let globals = (start > 0).then_some(0);
+        globals.into_iter().chain(start..self.scopes.len())
+    }
+}
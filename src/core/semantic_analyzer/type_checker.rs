@@ -0,0 +1,582 @@
+use std::collections::HashMap;
+
+use crate::core::diagnostics::closest_match;
+use crate::core::parser::ast::{Expression, Op, Parameter, Statement, Type};
+use crate::core::semantic_analyzer::defination::{load_native_functions, AnalysisError};
+use crate::core::token::Span;
+
+// The parameter and return types of a function declared in the script.
+#[derive(Debug, Clone)]
+pub struct Signature {
+    pub parameters: Vec<Type>,
+    pub return_type: Type,
+}
+
+impl Signature {
+    pub fn new(parameters: &[Parameter], return_type: Type) -> Self {
+        Signature {
+            parameters: parameters
+                .iter()
+                .map(|Parameter::Identifier(_, parameter_type)| parameter_type.clone())
+                .collect(),
+            return_type,
+        }
+    }
+}
+
+// Checks the types of a program without running it. Unlike the analyzer, which only looks at the
+// code that actually runs, every branch and loop body is checked, whatever its condition.
+pub struct TypeChecker {
+    // The variables declared at this point, innermost block last.
+    scopes: Vec<HashMap<String, Type>>,
+    // Where the scopes of the function being checked start, or 0 outside of functions. A
+    // function body sees its own scopes and the global one, like when it runs.
+    first_local_scope: usize,
+    functions: HashMap<String, Signature>,
+    is_inside_loop: bool,
+    // The return type of the function being checked, if any.
+    return_type: Option<(String, Type)>,
+}
+
+impl Default for TypeChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TypeChecker {
+    pub fn new() -> Self {
+        TypeChecker {
+            scopes: vec![HashMap::new()],
+            first_local_scope: 0,
+            functions: HashMap::new(),
+            is_inside_loop: false,
+            return_type: None,
+        }
+    }
+
+    // Makes a variable defined outside of the checked code visible, e.g. one defined on an
+    // earlier line of the REPL.
+    pub fn declare_variable(&mut self, name: &str, variable_type: Type) {
+        self.scopes[0].insert(name.to_string(), variable_type);
+    }
+
+    pub fn declare_function(&mut self, name: &str, signature: Signature) {
+        self.functions.insert(name.to_string(), signature);
+    }
+
+    // Checks the statements and fills in the type of every expression in them, so that tools can
+    // read the typed syntax tree back.
+    pub fn check(&mut self, statements: &mut [Statement]) -> Result<(), AnalysisError> {
+        for index in 0..statements.len() {
+            // Functions can be called before they are declared, e.g. by each other, but not from
+            // before a `let` they come after, since they may read the variable it declares.
+            if index == 0 || matches!(statements[index - 1], Statement::Let(..)) {
+                self.declare_functions(&statements[index..])?;
+            }
+            self.check_statement(&mut statements[index])?;
+        }
+        Ok(())
+    }
+
+    // Declares the functions up to the next `let`.
+    fn declare_functions(&mut self, statements: &[Statement]) -> Result<(), AnalysisError> {
+        for statement in statements {
+            match statement {
+                Statement::Let(..) => break,
+                Statement::FunctionDeclaration(name, parameters, _, return_type, span) => {
+                    if self.functions.contains_key(name)
+                        || load_native_functions().contains_key(name)
+                    {
+                        return Err(AnalysisError::FunctionAlreadyDefined {
+                            function_name: name.to_string(),
+                            span: *span,
+                        });
+                    }
+                    let return_type = return_type.clone().unwrap_or(Type::Void);
+                    self.declare_function(name, Signature::new(parameters, return_type));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn check_statement(&mut self, statement: &mut Statement) -> Result<(), AnalysisError> {
+        let span = statement.span();
+        match statement {
+            Statement::Let(name, annotation, expression, _) => {
+                // Shadowing a variable of an enclosing block is fine.
+                if self.scopes.last().unwrap().contains_key(name) {
+                    return Err(AnalysisError::VariableAlreadyDefined {
+                        variable_name: name.to_string(),
+                        span,
+                    });
+                }
+                let mut variable_type = self.check_expression(expression)?;
+                // The annotation wins where the initializer can't tell, e.g. for `[]`.
+                if let Some(annotation) = annotation {
+                    variable_type = match annotation.coerce(&variable_type) {
+                        Some(variable_type) => variable_type,
+                        None => {
+                            return Err(AnalysisError::MismatchedTypes {
+                                expected: annotation.to_string(),
+                                found: variable_type.to_string(),
+                                span: expression.span(),
+                            })
+                        }
+                    };
+                    expression.set_expression_type(variable_type.clone());
+                }
+                self.scopes
+                    .last_mut()
+                    .unwrap()
+                    .insert(name.to_string(), variable_type);
+            }
+            Statement::Assignment(_, expression, _)
+            | Statement::ExpressionStatement(expression, _) => {
+                self.check_expression(expression)?;
+            }
+            Statement::IfStatement(condition, statements, else_statements, _) => {
+                self.check_condition(condition)?;
+                self.check_block(statements)?;
+                if let Some(else_statements) = else_statements {
+                    self.check_block(else_statements)?;
+                }
+            }
+            Statement::ForStatement(init, condition, increment, statements, _) => {
+                self.check_expression(init)?;
+                self.check_condition(condition)?;
+                self.check_expression(increment)?;
+                self.check_loop(statements)?;
+            }
+            Statement::WhileStatement(condition, statements, _) => {
+                self.check_condition(condition)?;
+                self.check_loop(statements)?;
+            }
+            Statement::Break(_) | Statement::Continue(_) if !self.is_inside_loop => {
+                let keyword = match statement {
+                    Statement::Break(_) => "break",
+                    _ => "continue",
+                };
+                return Err(AnalysisError::LoopControlOutsideLoop {
+                    keyword: keyword.to_string(),
+                    span,
+                });
+            }
+            Statement::Break(_) | Statement::Continue(_) => {}
+            Statement::ArrayAssignment(name, index, value, _) => {
+                let element_type = self.check_array_access(name, index, span)?;
+                let value_type = self.check_expression(value)?;
+                let Some(element_type) = element_type.coerce(&value_type) else {
+                    return Err(AnalysisError::MismatchedTypes {
+                        expected: element_type.to_string(),
+                        found: value_type.to_string(),
+                        span: value.span(),
+                    });
+                };
+                self.refine(name, Type::Array(Box::new(element_type)));
+            }
+            // Already declared by check.
+            Statement::FunctionDeclaration(name, parameters, statements, return_type, _) => {
+                let return_type = return_type.clone().unwrap_or(Type::Void);
+                self.check_function(name, parameters, statements, return_type, span)?;
+            }
+            Statement::Return(expression, _) => {
+                let Some((function_name, return_type)) = self.return_type.clone() else {
+                    return Err(AnalysisError::ReturnOutsideFunction { span });
+                };
+                let found = match expression {
+                    Some(expression) => self.check_expression(expression)?,
+                    None => Type::Void,
+                };
+                if return_type.coerce(&found).is_none() {
+                    return Err(AnalysisError::MismatchedTypes {
+                        expected: format!("{} returned from `{}`", return_type, function_name),
+                        found: found.to_string(),
+                        span,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // The body of a function only sees its parameters, and whatever functions are declared.
+    fn check_function(
+        &mut self,
+        name: &str,
+        parameters: &[Parameter],
+        statements: &mut [Statement],
+        return_type: Type,
+        span: Span,
+    ) -> Result<(), AnalysisError> {
+        let parameters = parameters
+            .iter()
+            .map(|Parameter::Identifier(name, parameter_type)| {
+                (name.to_string(), parameter_type.clone())
+            })
+            .collect();
+        let first_local_scope = std::mem::replace(&mut self.first_local_scope, self.scopes.len());
+        self.scopes.push(parameters);
+        let is_inside_loop = std::mem::replace(&mut self.is_inside_loop, false);
+        let outer_return_type = self
+            .return_type
+            .replace((name.to_string(), return_type.clone()));
+
+        let result = self.check(statements);
+
+        self.scopes.truncate(self.first_local_scope);
+        self.first_local_scope = first_local_scope;
+        self.is_inside_loop = is_inside_loop;
+        self.return_type = outer_return_type;
+        result?;
+
+        // Falling off the end of the body is the same as `return;`.
+        if return_type != Type::Void && !always_returns(statements) {
+            return Err(AnalysisError::MismatchedTypes {
+                expected: format!("{} returned from `{}`", return_type, name),
+                found: Type::Void.to_string(),
+                span,
+            });
+        }
+        Ok(())
+    }
+
+    fn check_block(&mut self, statements: &mut [Statement]) -> Result<(), AnalysisError> {
+        self.scopes.push(HashMap::new());
+        let result = self.check(statements);
+        self.scopes.pop();
+        result
+    }
+
+    fn check_loop(&mut self, statements: &mut [Statement]) -> Result<(), AnalysisError> {
+        let is_inside_loop = std::mem::replace(&mut self.is_inside_loop, true);
+        let result = self.check_block(statements);
+        self.is_inside_loop = is_inside_loop;
+        result
+    }
+
+    fn check_condition(&mut self, condition: &mut Expression) -> Result<(), AnalysisError> {
+        let condition_type = self.check_expression(condition)?;
+        if condition_type != Type::Bool {
+            return Err(AnalysisError::NonBooleanCondition {
+                expected: "Boolean".to_string(),
+                found: condition_type.to_string(),
+                span: condition.span(),
+            });
+        }
+        Ok(())
+    }
+
+    fn lookup(&self, name: &str) -> Option<&Type> {
+        self.visible_scopes()
+            .rev()
+            .find_map(|index| self.scopes[index].get(name))
+    }
+
+    // The indexes of the scopes whose variables are visible, outermost first.
+    fn visible_scopes(&self) -> impl DoubleEndedIterator<Item = usize> {
+        let globals = (self.first_local_scope > 0).then_some(0);
+        globals
+            .into_iter()
+            .chain(self.first_local_scope..self.scopes.len())
+    }
+
+    // Every visible variable name, to suggest one in case of a typo.
+    fn visible_names(&self) -> impl Iterator<Item = &str> {
+        self.visible_scopes()
+            .flat_map(|index| self.scopes[index].keys().map(String::as_str))
+    }
+
+    // Narrows down the type of a variable once more is known about it, e.g. once a string is
+    // stored in what was an empty array.
+    fn refine(&mut self, name: &str, variable_type: Type) {
+        if let Some(index) = self
+            .visible_scopes()
+            .rev()
+            .find(|index| self.scopes[*index].contains_key(name))
+        {
+            self.scopes[index].insert(name.to_string(), variable_type);
+        }
+    }
+
+    fn lookup_variable(&self, name: &str, span: Span) -> Result<Type, AnalysisError> {
+        match self.lookup(name) {
+            Some(variable_type) => Ok(variable_type.clone()),
+            None => Err(AnalysisError::UndefinedVariable {
+                expected: name.to_string(),
+                suggestion: closest_match(name, self.visible_names()),
+                span,
+            }),
+        }
+    }
+
+    // The type of the elements of the array called name, after checking that the index is an
+    // integer.
+    fn check_array_access(
+        &mut self,
+        name: &str,
+        index: &mut Expression,
+        span: Span,
+    ) -> Result<Type, AnalysisError> {
+        let variable_type = match self.lookup(name) {
+            Some(variable_type) => variable_type.clone(),
+            None => {
+                return Err(AnalysisError::UndefinedVariable {
+                    expected: name.to_string(),
+                    suggestion: closest_match(name, self.visible_names()),
+                    span,
+                })
+            }
+        };
+        let Type::Array(element_type) = variable_type else {
+            return Err(AnalysisError::MismatchedTypes {
+                expected: Type::Array(Box::new(Type::Any)).to_string(),
+                found: variable_type.to_string(),
+                span,
+            });
+        };
+
+        let index_type = self.check_expression(index)?;
+        if index_type != Type::Integer {
+            return Err(AnalysisError::MismatchedTypes {
+                expected: Type::Integer.to_string(),
+                found: index_type.to_string(),
+                span: index.span(),
+            });
+        }
+        Ok(*element_type)
+    }
+
+    // Infers the type of the expression and records it in the expression.
+    pub fn check_expression(&mut self, expression: &mut Expression) -> Result<Type, AnalysisError> {
+        let expression_type = self.infer(expression)?;
+        expression.set_expression_type(expression_type.clone());
+        Ok(expression_type)
+    }
+
+    fn infer(&mut self, expression: &mut Expression) -> Result<Type, AnalysisError> {
+        let span = expression.span();
+        match expression {
+            Expression::StringLiteral(..) => Ok(Type::String),
+            Expression::Boolean(..) => Ok(Type::Bool),
+            // The lexer has already turned hex, binary and octal literals into plain integers.
+            Expression::Number(value, _, _) => {
+                if value.contains(['.', 'e', 'E']) {
+                    Ok(Type::Decimal)
+                } else {
+                    Ok(Type::Integer)
+                }
+            }
+            // The element type is whatever all of the elements have in common, and unknown for
+            // an empty array until something is stored in it.
+            Expression::Array(elements, _, _) => {
+                let mut element_type = Type::Any;
+                for element in elements {
+                    let found = self.check_expression(element)?;
+                    element_type = match element_type.promote(&found) {
+                        Some(element_type) => element_type,
+                        None => {
+                            return Err(AnalysisError::MismatchedTypes {
+                                expected: element_type.to_string(),
+                                found: found.to_string(),
+                                span: element.span(),
+                            })
+                        }
+                    };
+                }
+                Ok(Type::Array(Box::new(element_type)))
+            }
+            Expression::Identifier(name, _, _) => self.lookup_variable(name, span),
+            Expression::ArrayAccess(name, index, _, _) => {
+                self.check_array_access(name, index, span)
+            }
+            Expression::FunctionCall(name, arguments, _, _) => {
+                self.check_function_call(name, arguments, span)
+            }
+            Expression::UnaryOp(operator, operand, _, _) => {
+                let operand_type = self.check_expression(operand)?;
+                let expected = match operator {
+                    Op::Add | Op::Subtract if operand_type == Type::Decimal => Type::Decimal,
+                    Op::Add | Op::Subtract => Type::Integer,
+                    Op::Not => Type::Bool,
+                    Op::BitwiseNot => Type::Integer,
+                    _ => {
+                        return Err(AnalysisError::IllegalOperation {
+                            expected: "`+`, `-`, `!` or `~`".to_string(),
+                            found: format!("`{}`", operator),
+                            operation: operator.clone(),
+                            span,
+                        })
+                    }
+                };
+                if operand_type != expected {
+                    return Err(AnalysisError::IllegalOperation {
+                        expected: expected.to_string(),
+                        found: operand_type.to_string(),
+                        operation: operator.clone(),
+                        span,
+                    });
+                }
+                Ok(expected)
+            }
+            Expression::BinOp(left, Op::Assign, right, _, _) => {
+                let (name, target_type) = match left.as_mut() {
+                    Expression::Identifier(name, _, _) => {
+                        (name.clone(), self.lookup_variable(name, span)?)
+                    }
+                    Expression::ArrayAccess(name, index, _, _) => {
+                        (name.clone(), self.check_array_access(name, index, span)?)
+                    }
+                    _ => {
+                        return Err(AnalysisError::IllegalOperation {
+                            expected: "Identifier".to_string(),
+                            found: "Not Identifier".to_string(),
+                            operation: Op::Assign,
+                            span,
+                        })
+                    }
+                };
+                let value_type = self.check_expression(right)?;
+                let Some(value_type) = target_type.coerce(&value_type) else {
+                    return Err(AnalysisError::IllegalOperation {
+                        expected: target_type.to_string(),
+                        found: value_type.to_string(),
+                        operation: Op::Assign,
+                        span,
+                    });
+                };
+                let variable_type = match left.as_ref() {
+                    Expression::ArrayAccess(..) => Type::Array(Box::new(value_type.clone())),
+                    _ => value_type.clone(),
+                };
+                self.refine(&name, variable_type);
+                left.set_expression_type(value_type.clone());
+                Ok(value_type)
+            }
+            Expression::BinOp(left, operator, right, _, _) => {
+                let left_type = self.check_expression(left)?;
+                let right_type = self.check_expression(right)?;
+                binary_operation_type(operator, &left_type, &right_type).ok_or_else(|| {
+                    AnalysisError::IllegalOperation {
+                        expected: expected_operands(operator).to_string(),
+                        found: format!("{} and {}", left_type, right_type),
+                        operation: operator.clone(),
+                        span,
+                    }
+                })
+            }
+        }
+    }
+
+    fn check_function_call(
+        &mut self,
+        name: &str,
+        arguments: &mut [Expression],
+        span: Span,
+    ) -> Result<Type, AnalysisError> {
+        let native_functions = load_native_functions();
+        let signature = match (self.functions.get(name), native_functions.get(name)) {
+            (Some(signature), _) => signature.clone(),
+            (None, Some(native_function)) => Signature {
+                parameters: native_function.parameters_types.clone(),
+                return_type: native_function.return_type.clone(),
+            },
+            (None, None) => {
+                let known_functions = self
+                    .functions
+                    .keys()
+                    .chain(native_functions.keys())
+                    .map(String::as_str);
+                return Err(AnalysisError::UndefinedFunction {
+                    expected: name.to_string(),
+                    found: name.to_string(),
+                    suggestion: closest_match(name, known_functions),
+                    span,
+                });
+            }
+        };
+
+        if signature.parameters.len() != arguments.len() {
+            return Err(AnalysisError::ArgumentCountMismatch {
+                expected: signature.parameters.len().to_string(),
+                found: arguments.len().to_string(),
+                span,
+            });
+        }
+        for (i, (parameter_type, argument)) in
+            signature.parameters.iter().zip(arguments).enumerate()
+        {
+            let argument_type = self.check_expression(argument)?;
+            if parameter_type.coerce(&argument_type).is_none() {
+                return Err(AnalysisError::ArgumentTypeMismatch {
+                    argument_name: i.to_string(),
+                    expected: parameter_type.to_string(),
+                    found: argument_type.to_string(),
+                    span: argument.span(),
+                });
+            }
+        }
+        Ok(signature.return_type)
+    }
+}
+
+// The type of `left operator right`, or None if the operator doesn't apply to those types. Mixed
+// integer and decimal operands are promoted to decimals.
+fn binary_operation_type(operator: &Op, left: &Type, right: &Type) -> Option<Type> {
+    let is_numeric = left.is_numeric() && right.is_numeric();
+    match operator {
+        Op::Add if *left == Type::String && *right == Type::String => Some(Type::String),
+        Op::Add | Op::Subtract | Op::Multiply | Op::Divide | Op::Modulo | Op::Power
+            if is_numeric =>
+        {
+            if *left == Type::Decimal || *right == Type::Decimal {
+                Some(Type::Decimal)
+            } else {
+                Some(Type::Integer)
+            }
+        }
+        Op::LessThan | Op::LessThanEqualTo | Op::GreaterThan | Op::GreaterThanEqualTo
+            if is_numeric =>
+        {
+            Some(Type::Bool)
+        }
+        Op::Equals | Op::NotEquals if left.promote(right).is_some() => Some(Type::Bool),
+        Op::And | Op::Or if *left == Type::Bool && *right == Type::Bool => Some(Type::Bool),
+        Op::BitwiseAnd | Op::BitwiseOr | Op::BitwiseXor | Op::ShiftLeft | Op::ShiftRight
+            if *left == Type::Integer && *right == Type::Integer =>
+        {
+            Some(Type::Integer)
+        }
+        _ => None,
+    }
+}
+
+// What the operands of operator may be, for errors.
+fn expected_operands(operator: &Op) -> &'static str {
+    match operator {
+        Op::Add => "Integer, String, Decimal",
+        Op::Subtract | Op::Multiply | Op::Divide | Op::Modulo | Op::Power => "Integer, Decimal",
+        Op::LessThan | Op::LessThanEqualTo | Op::GreaterThan | Op::GreaterThanEqualTo => {
+            "Integer, Decimal"
+        }
+        Op::Equals | Op::NotEquals => "two values of the same type",
+        Op::And | Op::Or => "Bool",
+        Op::BitwiseAnd | Op::BitwiseOr | Op::BitwiseXor | Op::ShiftLeft | Op::ShiftRight => {
+            "Integer"
+        }
+        Op::Not | Op::BitwiseNot | Op::Assign => "a binary operator",
+    }
+}
+
+// Whether every way through the statements ends in a return.
+fn always_returns(statements: &[Statement]) -> bool {
+    statements.iter().any(|statement| match statement {
+        Statement::Return(..) => true,
+        Statement::IfStatement(_, statements, Some(else_statements), _) => {
+            always_returns(statements) && always_returns(else_statements)
+        }
+        _ => false,
+    })
+}
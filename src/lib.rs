@@ -1,31 +1,143 @@
-mod core;
+pub mod core;
+
+use crate::core::diagnostics::Diagnostic;
+use crate::core::lexer::definition::LexError;
+use crate::core::parser::ast::Statement;
+use crate::core::parser::definition::ParseError;
+use crate::core::semantic_analyzer::defination::AnalysisError;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+pub use crate::core::semantic_analyzer::host_io::{HostIo, StdIo, StreamIo};
+
 pub struct Aryalang {
     input: String,
+    io: Arc<Mutex<dyn HostIo>>,
+}
+
+// Everything that can go wrong while running a script.
+#[derive(Debug, Clone)]
+pub enum Error {
+    Lex(LexError),
+    // Every error the parser found, in the order they appear in the input.
+    Parse(Vec<ParseError>),
+    Analysis(AnalysisError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Lex(e) => write!(f, "Syntax Error: {}", e),
+            Error::Parse(errors) => {
+                for (i, e) in errors.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "Parse Error: {}", e)?;
+                }
+                Ok(())
+            }
+            Error::Analysis(e) => write!(f, "Error: {}", e),
+        }
+    }
+}
+
+impl Error {
+    // One diagnostic per error, in the order they appear in the input.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        match self {
+            Error::Lex(e) => vec![e.diagnostic()],
+            Error::Parse(errors) => errors.iter().map(ParseError::diagnostic).collect(),
+            Error::Analysis(e) => vec![e.diagnostic()],
+        }
+    }
+
+    // Renders every diagnostic along with the lines of source it points at, separated by blank
+    // lines.
+    pub fn render(&self, file_name: &str, source: &str) -> String {
+        self.diagnostics()
+            .iter()
+            .map(|diagnostic| diagnostic.render(file_name, source))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Lex(e) => Some(e),
+            Error::Parse(errors) => errors
+                .first()
+                .map(|e| e as &(dyn std::error::Error + 'static)),
+            Error::Analysis(e) => Some(e),
+        }
+    }
+}
+
+impl From<LexError> for Error {
+    fn from(e: LexError) -> Self {
+        Error::Lex(e)
+    }
+}
+
+impl From<Vec<ParseError>> for Error {
+    fn from(errors: Vec<ParseError>) -> Self {
+        Error::Parse(errors)
+    }
+}
+
+impl From<AnalysisError> for Error {
+    fn from(e: AnalysisError) -> Self {
+        Error::Analysis(e)
+    }
 }
 
 impl Aryalang {
     pub fn new(input: String) -> Self {
-        Aryalang { input }
+        Aryalang {
+            input,
+            io: Arc::new(Mutex::new(StdIo)),
+        }
     }
 
+    // Runs the script against the given I/O instead of the terminal. Keep a clone of the `Arc` to
+    // read back whatever the script wrote.
+    pub fn with_io(input: String, io: Arc<Mutex<dyn HostIo>>) -> Self {
+        Aryalang { input, io }
+    }
+
+    // Runs the script and writes any error to the same output as the script. Use try_run to get
+    // the error back instead.
     pub fn run(&mut self) {
+        if let Err(e) = self.try_run() {
+            // There is nowhere left to report a failed write.
+            let _ = self.io.lock().unwrap().write(&format!("{}\n", e));
+        }
+    }
+
+    // Parses and type checks the script without running it. The syntax tree comes back with the
+    // type of every expression filled in.
+    pub fn check(&self) -> Result<Vec<Statement>, Error> {
         let lexer = core::Lexer::new(&self.input);
-        let tokens = lexer.tokenize();
+        let tokens = lexer.tokenize()?;
+        let mut statements = core::Parser::new(&tokens).parse()?;
+        core::TypeChecker::new().check(&mut statements)?;
+        Ok(statements)
+    }
+
+    // Same as `run`, but hands the error back to the caller instead of printing it.
+    pub fn try_run(&mut self) -> Result<(), Error> {
+        let lexer = core::Lexer::new(&self.input);
+        let tokens = lexer.tokenize()?;
         let mut parser = core::Parser::new(&tokens);
-        let result = parser.parse().expect("Parse Error: \n");
-        if result.len() == 0 {
-            print!("{:?}", "No tokens to execute.");
-            return;
+        let result = parser.parse()?;
+        if result.is_empty() {
+            return Ok(());
         }
         let mut seman_analyzer = core::Analyzer::new(result);
-        let result_option = seman_analyzer.parse(); // returns Result<bool, error>
-        match result_option {
-            Ok(_value) => {}
-            Err(_e) => {
-                // Handle the error case, e.g., log the error, return from function, etc.
-                // You can use _e to access the error if needed
-                println!("Error: {:?}", _e);
-            }
-        }
+        seman_analyzer.set_io(self.io.clone());
+        seman_analyzer.parse()?;
+        Ok(())
     }
 }
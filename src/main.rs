@@ -1,13 +1,105 @@
-use aryalang::Aryalang;
-use std::fs;
+use aryalang::core::token::Token;
+use aryalang::core::{Lexer, Parser};
+use aryalang::{Aryalang, Error};
+use std::{env, fs, process};
+
+mod repl;
+
+// Exit codes, so that build scripts can tell the failures apart.
+const EXIT_USAGE: i32 = 1;
+const EXIT_PARSE_ERROR: i32 = 2;
+const EXIT_ANALYSIS_ERROR: i32 = 3;
+const EXIT_RUNTIME_ERROR: i32 = 4;
+
+const USAGE: &str = "Usage: aryalang <command> <file>
+       aryalang repl
+
+Commands:
+    run     Parse, analyze and execute the file
+    check   Check the file for errors without executing it
+    tokens  Print the tokens produced by the lexer
+    ast     Print the syntax tree produced by the parser
+    repl    Start an interactive session";
 
 fn main() {
-    // expect a parameter which will be a file name and read the contents of the file and pass it to the Aryalang::new() function
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (command, file_name) = match args.as_slice() {
+        [command] if command == "repl" => {
+            repl::start();
+            return;
+        }
+        [command, file_name] => (command.as_str(), file_name.as_str()),
+        _ => usage_error(),
+    };
 
-    // read a file and get its contents
-    let contents = fs::read_to_string("main.al").expect("Something went wrong reading the file");
+    let contents = match fs::read_to_string(file_name) {
+        Ok(contents) => contents,
+        Err(e) => {
+            eprintln!("Could not read {}: {}", file_name, e);
+            process::exit(EXIT_USAGE);
+        }
+    };
 
-    let mut al = Aryalang::new(contents);
+    match command {
+        "run" => run(file_name, contents),
+        "check" => check(file_name, &contents),
+        "tokens" => tokens(file_name, &contents),
+        "ast" => ast(file_name, &contents),
+        _ => usage_error(),
+    }
+}
+
+fn usage_error() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(EXIT_USAGE);
+}
+
+fn run(file_name: &str, contents: String) {
+    if let Err(e) = Aryalang::new(contents.clone()).try_run() {
+        eprint!("{}", e.render(file_name, &contents));
+        process::exit(exit_code_for(&e));
+    }
+}
+
+fn check(file_name: &str, contents: &str) {
+    // Only types are checked, nothing is executed.
+    if let Err(e) = Aryalang::new(contents.to_string()).check() {
+        eprint!("{}", e.render(file_name, contents));
+        process::exit(exit_code_for(&e));
+    }
+}
+
+fn tokens(file_name: &str, contents: &str) {
+    for token in lex(file_name, contents) {
+        println!("{}:{} {:?}", token.span.line, token.span.column, token.kind);
+    }
+}
+
+fn ast(file_name: &str, contents: &str) {
+    let tokens = lex(file_name, contents);
+    match Parser::new(&tokens).parse() {
+        Ok(statements) => println!("{:#?}", statements),
+        Err(e) => {
+            eprint!("{}", Error::Parse(e).render(file_name, contents));
+            process::exit(EXIT_PARSE_ERROR);
+        }
+    }
+}
+
+fn lex(file_name: &str, contents: &str) -> Vec<Token> {
+    match Lexer::new(contents).tokenize() {
+        Ok(tokens) => tokens,
+        Err(e) => {
+            eprint!("{}", Error::Lex(e).render(file_name, contents));
+            process::exit(EXIT_PARSE_ERROR);
+        }
+    }
+}
 
-    al.run();
+fn exit_code_for(error: &Error) -> i32 {
+    match error {
+        Error::Lex(_) | Error::Parse(_) => EXIT_PARSE_ERROR,
+        Error::Analysis(e) if e.is_runtime() => EXIT_RUNTIME_ERROR,
+        Error::Analysis(_) => EXIT_ANALYSIS_ERROR,
+    }
 }
@@ -0,0 +1,239 @@
+use aryalang::core::lexer::definition::LexError;
+use aryalang::core::parser::ast::{Expression, Op, Statement, Type};
+use aryalang::core::token::TokenKind;
+use aryalang::core::{Analyzer, Lexer, Parser};
+use aryalang::Error;
+use std::io::{self, BufRead, Write};
+
+const PROMPT: &str = ">> ";
+const CONTINUATION_PROMPT: &str = ".. ";
+// What errors call the input, in place of a file name.
+const SOURCE_NAME: &str = "<repl>";
+
+// Reads statements from stdin and evaluates them one at a time. Every statement runs against the
+// same analyzer, so variables defined on one line stay visible on the next.
+pub fn start() {
+    let mut analyzer = Analyzer::new(vec![]);
+    let mut buffer = String::new();
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    loop {
+        if buffer.is_empty() {
+            print!("{}", PROMPT);
+        } else {
+            print!("{}", CONTINUATION_PROMPT);
+        }
+        io::stdout().flush().expect("Could not flush stdout");
+
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            _ => break,
+        };
+
+        if buffer.is_empty() && line.trim().is_empty() {
+            continue;
+        }
+
+        buffer.push_str(&line);
+        buffer.push('\n');
+        if !is_complete(&buffer) {
+            continue;
+        }
+
+        evaluate(&mut analyzer, &buffer);
+        buffer.clear();
+    }
+    println!();
+}
+
+// The input is complete once every block has been closed and the last statement has been
+// terminated, otherwise we keep reading lines. An `if` may still be followed by an `else` on the
+// next line, so after one we wait for that line, or for a blank one to end it.
+fn is_complete(input: &str) -> bool {
+    // A string or comment that is still open may be closed on the next line, any other error is
+    // reported once the input is evaluated.
+    let tokens = match Lexer::new(input).tokenize() {
+        Ok(tokens) => tokens,
+        Err(LexError::UnterminatedString { .. } | LexError::UnterminatedComment { .. }) => {
+            return false
+        }
+        Err(_) => return true,
+    };
+    let mut depth = 0;
+    // The first token of the last statement, and whether the last block it opened follows an
+    // `else`.
+    let mut statement_start = None;
+    let mut else_block = false;
+    let mut starts_statement = true;
+    let mut previous = None;
+    for token in &tokens {
+        // An `else` carries on the `if` before it.
+        if depth == 0 && starts_statement && token.kind != TokenKind::Else {
+            statement_start = Some(&token.kind);
+        }
+        starts_statement = false;
+        match token.kind {
+            TokenKind::LBrace => {
+                if depth == 0 {
+                    else_block = previous == Some(&TokenKind::Else);
+                }
+                depth += 1;
+            }
+            TokenKind::RBrace => {
+                depth -= 1;
+                starts_statement = depth == 0;
+            }
+            TokenKind::Semicolon => starts_statement = depth == 0,
+            _ => {}
+        }
+        previous = Some(&token.kind);
+    }
+
+    match tokens.last() {
+        Some(token) if token.kind == TokenKind::RBrace && depth <= 0 => {
+            let open_if = statement_start == Some(&TokenKind::If) && !else_block;
+            depth < 0 || !open_if || ends_with_blank_line(input)
+        }
+        Some(token) => depth <= 0 && token.kind == TokenKind::Semicolon,
+        None => true,
+    }
+}
+
+fn ends_with_blank_line(input: &str) -> bool {
+    input
+        .lines()
+        .last()
+        .is_some_and(|line| line.trim().is_empty())
+}
+
+fn evaluate(analyzer: &mut Analyzer, input: &str) {
+    let tokens = match Lexer::new(input).tokenize() {
+        Ok(tokens) => tokens,
+        Err(e) => {
+            eprint!("{}", Error::Lex(e).render(SOURCE_NAME, input));
+            return;
+        }
+    };
+    let statements = match Parser::new(&tokens).parse() {
+        Ok(statements) => statements,
+        Err(e) => {
+            eprint!("{}", Error::Parse(e).render(SOURCE_NAME, input));
+            return;
+        }
+    };
+
+    for statement in statements {
+        if let Statement::ExpressionStatement(expression, _) = &statement {
+            if !is_assignment(expression) {
+                match analyzer.evaluate(expression) {
+                    Ok(result) => {
+                        if result.expression_type != Type::Void {
+                            println!("{}", result.value);
+                        }
+                    }
+                    Err(e) => {
+                        eprint!("{}", Error::Analysis(e).render(SOURCE_NAME, input));
+                        return;
+                    }
+                }
+                continue;
+            }
+        }
+
+        analyzer.statements = vec![statement];
+        if let Err(e) = analyzer.parse() {
+            eprint!("{}", Error::Analysis(e).render(SOURCE_NAME, input));
+            return;
+        }
+    }
+}
+
+fn is_assignment(expression: &Expression) -> bool {
+    matches!(expression, Expression::BinOp(_, Op::Assign, ..))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Feeds the lines one at a time, the way start does, and returns the inputs that would be
+    // evaluated.
+    fn inputs(lines: &[&str]) -> Vec<String> {
+        let mut inputs = vec![];
+        let mut buffer = String::new();
+        for line in lines {
+            if buffer.is_empty() && line.trim().is_empty() {
+                continue;
+            }
+            buffer.push_str(line);
+            buffer.push('\n');
+            if is_complete(&buffer) {
+                inputs.push(std::mem::take(&mut buffer));
+            }
+        }
+        inputs
+    }
+
+    #[test]
+    fn blocks_can_span_lines() {
+        assert_eq!(
+            inputs(&["while x < 3 {", "  x = x + 1;", "}", "x;"]),
+            vec!["while x < 3 {\n  x = x + 1;\n}\n", "x;\n"]
+        );
+        assert_eq!(
+            inputs(&["function f(): int {", "  return 1;", "}"]),
+            vec!["function f(): int {\n  return 1;\n}\n"]
+        );
+    }
+
+    #[test]
+    fn strings_can_span_lines() {
+        assert_eq!(
+            inputs(&["let s = \"one", "two\";"]),
+            vec!["let s = \"one\ntwo\";\n"]
+        );
+        assert_eq!(inputs(&["let s = \"one"]), Vec::<String>::new());
+    }
+
+    #[test]
+    fn else_can_go_on_the_next_line() {
+        assert_eq!(
+            inputs(&["if x {", "  x = 1;", "}", "else {", "  x = 2;", "}", "x;"]),
+            vec!["if x {\n  x = 1;\n}\nelse {\n  x = 2;\n}\n", "x;\n"]
+        );
+        assert_eq!(
+            inputs(&["if x {", "}", "else if y {", "}", "", "x;"]),
+            vec!["if x {\n}\nelse if y {\n}\n\n", "x;\n"]
+        );
+        // Without an `else`, a blank line or the next statement ends the `if`.
+        assert_eq!(inputs(&["if x {", "}", ""]), vec!["if x {\n}\n\n"]);
+        assert_eq!(inputs(&["if x {", "}", "x;"]), vec!["if x {\n}\nx;\n"]);
+    }
+
+    // The value of an expression, evaluated against the analyzer.
+    fn value(analyzer: &Analyzer, source: &str) -> String {
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let statements = Parser::new(&tokens).parse().unwrap();
+        let [Statement::ExpressionStatement(expression, _)] = &statements[..] else {
+            panic!("expected an expression");
+        };
+        analyzer.evaluate(expression).unwrap().value.to_string()
+    }
+
+    #[test]
+    fn state_persists_between_lines() {
+        let mut analyzer = Analyzer::new(vec![]);
+        for input in inputs(&[
+            "let x = 1;",
+            "function double(n: int): int {",
+            "  return n * 2;",
+            "}",
+            "x = double(x) + 1;",
+        ]) {
+            evaluate(&mut analyzer, &input);
+        }
+        assert_eq!(value(&analyzer, "x;"), "3");
+        assert_eq!(value(&analyzer, "double(x);"), "6");
+    }
+}
@@ -0,0 +1,44 @@
+mod common;
+
+use aryalang::core::semantic_analyzer::defination::AnalysisError;
+use aryalang::Error;
+use common::run;
+
+#[test]
+fn storing_past_the_end_grows_the_array() {
+    let output = run("
+        let a = [1];
+        a[3] = 4;
+        println(inttostr(a[2]));
+        println(inttostr(a[3]));
+    ");
+    assert_eq!(output.unwrap(), "0\n4\n");
+}
+
+#[test]
+fn negative_indexes_are_out_of_bounds() {
+    let result = run("
+        let a = [1, 2];
+        a[-1] = 3;
+    ");
+    let Err(Error::Analysis(error)) = result else {
+        panic!("expected a runtime error");
+    };
+    assert!(matches!(
+        error,
+        AnalysisError::OutOfBoundsException { ref found, .. } if found == "-1"
+    ));
+    assert!(error.is_runtime());
+    assert!(matches!(
+        run("let a = [1, 2]; println(inttostr(a[-1]));"),
+        Err(Error::Analysis(AnalysisError::OutOfBoundsException { .. }))
+    ));
+}
+
+#[test]
+fn arrays_cannot_grow_without_bound() {
+    assert!(matches!(
+        run("let a = [1]; a[1000000000000] = 2;"),
+        Err(Error::Analysis(AnalysisError::OutOfBoundsException { .. }))
+    ));
+}
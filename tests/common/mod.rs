@@ -0,0 +1,14 @@
+use aryalang::{Aryalang, Error, StreamIo};
+use std::io::Cursor;
+use std::sync::{Arc, Mutex};
+
+// Runs the script and returns everything it printed.
+pub fn run(source: &str) -> Result<String, Error> {
+    let io = Arc::new(Mutex::new(StreamIo::new(
+        Cursor::new(Vec::new()),
+        Vec::new(),
+    )));
+    Aryalang::with_io(source.to_string(), io.clone()).try_run()?;
+    let output = io.lock().unwrap().writer.clone();
+    Ok(String::from_utf8(output).unwrap())
+}
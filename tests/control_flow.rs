@@ -0,0 +1,148 @@
+mod common;
+
+use aryalang::core::parser::ast::Statement;
+use aryalang::core::semantic_analyzer::defination::AnalysisError;
+use aryalang::core::{Lexer, Parser};
+use aryalang::Error;
+use common::run;
+
+#[test]
+fn else_if_chains_run_the_first_matching_branch() {
+    let output = run("
+        let n = 0;
+        for (n = 0; n < 4; n = n + 1) {
+            if n == 0 {
+                println(\"zero\");
+            } else if n == 1 {
+                println(\"one\");
+            } else if n == 2 {
+                println(\"two\");
+            } else {
+                println(\"many\");
+            }
+        }
+    ");
+    assert_eq!(output.unwrap(), "zero\none\ntwo\nmany\n");
+}
+
+#[test]
+fn else_branches_are_optional() {
+    let output = run("
+        let x = 5;
+        if x > 10 {
+            println(\"big\");
+        } else if x > 1 {
+            println(\"medium\");
+        }
+        if x > 10 {
+            println(\"big\");
+        }
+        println(\"done\");
+    ");
+    assert_eq!(output.unwrap(), "medium\ndone\n");
+}
+
+#[test]
+fn else_if_nests_an_if_in_the_else_branch() {
+    let tokens = Lexer::new("if a { } else if b { } else { x = 1; }")
+        .tokenize()
+        .unwrap();
+    let statements = Parser::new(&tokens).parse().unwrap();
+    let [Statement::IfStatement(_, _, Some(else_statements), _)] = &statements[..] else {
+        panic!("expected an if statement with an else branch");
+    };
+    assert!(matches!(
+        &else_statements[..],
+        [Statement::IfStatement(_, _, Some(last), _)] if last.len() == 1
+    ));
+}
+
+#[test]
+fn else_needs_a_block_or_an_if() {
+    let tokens = Lexer::new("if a { } else x = 1;").tokenize().unwrap();
+    assert!(Parser::new(&tokens).parse().is_err());
+}
+
+#[test]
+fn while_loops_run_until_the_condition_is_false() {
+    let output = run("
+        let n = 1;
+        while n < 100 {
+            n = n * 3;
+        }
+        println(inttostr(n));
+        while false {
+            println(\"never\");
+        }
+    ");
+    assert_eq!(output.unwrap(), "243\n");
+}
+
+#[test]
+fn break_and_continue_work_in_both_kinds_of_loops() {
+    let output = run("
+        let i = 0;
+        while true {
+            i = i + 1;
+            if i == 2 {
+                continue;
+            }
+            if i == 5 {
+                break;
+            }
+            print(inttostr(i));
+        }
+        println(\"\");
+        // The increment still runs after continue.
+        for (i = 0; i < 10; i = i + 1) {
+            if i % 2 == 0 {
+                continue;
+            }
+            if i > 7 {
+                break;
+            }
+            print(inttostr(i));
+        }
+        println(\"\");
+    ");
+    assert_eq!(output.unwrap(), "134\n1357\n");
+}
+
+#[test]
+fn break_only_leaves_the_innermost_loop() {
+    let output = run("
+        let i = 0;
+        let j = 0;
+        for (i = 0; i < 3; i = i + 1) {
+            j = 0;
+            while true {
+                j = j + 1;
+                if j > i {
+                    break;
+                }
+            }
+            print(inttostr(j));
+        }
+    ");
+    assert_eq!(output.unwrap(), "123");
+}
+
+#[test]
+fn break_and_continue_outside_of_a_loop_are_errors() {
+    for source in [
+        "println(\"before\"); break;",
+        "if true { continue; }",
+        "while true { function f() { break; } }",
+    ] {
+        assert!(
+            matches!(
+                run(source),
+                Err(Error::Analysis(
+                    AnalysisError::LoopControlOutsideLoop { .. }
+                ))
+            ),
+            "{}",
+            source
+        );
+    }
+}
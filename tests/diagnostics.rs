@@ -0,0 +1,84 @@
+use aryalang::core::diagnostics::closest_match;
+use aryalang::Aryalang;
+
+// Checks the script and renders whatever went wrong, as the command-line driver shows it.
+fn rendered(source: &str) -> String {
+    Aryalang::new(source.to_string())
+        .check()
+        .unwrap_err()
+        .render("main.al", source)
+}
+
+#[test]
+fn errors_point_at_the_source_with_a_caret() {
+    assert_eq!(
+        rendered("let count = 1;\nprintln(inttostr(cont));\n"),
+        "\
+error: undefined variable `cont`
+ --> main.al:2:18
+  |
+2 | println(inttostr(cont));
+  |                  ^^^^
+  = help: did you mean `count`?
+"
+    );
+}
+
+#[test]
+fn typos_in_function_names_get_a_suggestion() {
+    assert_eq!(
+        rendered("prnitln(\"hi\");"),
+        "\
+error: undefined function `prnitln`
+ --> main.al:1:1
+  |
+1 | prnitln(\"hi\");
+  | ^^^^^^^^^^^^^
+  = help: did you mean `println`?
+"
+    );
+}
+
+#[test]
+fn every_parse_error_is_rendered() {
+    assert_eq!(
+        rendered("let x = (1;\n\n\n\n\n\n\n\n\nlet y = ;\n"),
+        "\
+parse error: expected `)` but found `;`
+ --> main.al:1:11
+  |
+1 | let x = (1;
+  |           ^
+
+parse error: expected an expression but found `;`
+  --> main.al:10:9
+   |
+10 | let y = ;
+   |         ^
+"
+    );
+}
+
+#[test]
+fn carets_line_up_after_tabs() {
+    assert!(rendered("\tlet x = y;").contains("1 | \tlet x = y;\n  | \t        ^\n"));
+}
+
+#[test]
+fn runtime_errors_say_so() {
+    let source = "let zero = 0;\nlet x = 1 / zero;";
+    let error = Aryalang::new(source.to_string()).try_run().unwrap_err();
+    assert!(error
+        .render("main.al", source)
+        .starts_with("runtime error: division by zero\n --> main.al:2:9\n"));
+}
+
+#[test]
+fn only_close_names_are_suggested() {
+    let names = ["count", "total", "println"];
+    assert_eq!(closest_match("cont", names), Some("count".to_string()));
+    assert_eq!(closest_match("totl", names), Some("total".to_string()));
+    assert_eq!(closest_match("xyz", names), None);
+    assert_eq!(closest_match("value", names), None);
+    assert!(!rendered("let x = xyz;").contains("help"));
+}
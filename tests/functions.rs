@@ -0,0 +1,169 @@
+mod common;
+
+use aryalang::core::parser::definition::ParseError;
+use aryalang::core::semantic_analyzer::defination::AnalysisError;
+use aryalang::Error;
+use common::run;
+
+#[test]
+fn functions_can_recurse() {
+    let output = run("
+        function factorial(n: int): int {
+            if n <= 1 {
+                return 1;
+            }
+            return n * factorial(n - 1);
+        }
+        println(inttostr(factorial(10)));
+    ");
+    assert_eq!(output.unwrap(), "3628800\n");
+}
+
+#[test]
+fn functions_without_a_return_type_return_nothing() {
+    let output = run("
+        function greet(name: string) {
+            println(\"hi \" + name);
+        }
+        greet(\"amy\");
+    ");
+    assert_eq!(output.unwrap(), "hi amy\n");
+}
+
+#[test]
+fn arguments_must_match_the_parameter_types() {
+    let result = run("
+        function double(n: int): int {
+            return n * 2;
+        }
+        println(inttostr(double(\"two\")));
+    ");
+    assert!(matches!(
+        result,
+        Err(Error::Analysis(AnalysisError::ArgumentTypeMismatch { .. }))
+    ));
+}
+
+#[test]
+fn calls_must_pass_every_argument() {
+    let result = run("
+        function add(a: int, b: int): int {
+            return a + b;
+        }
+        println(inttostr(add(1)));
+    ");
+    assert!(matches!(
+        result,
+        Err(Error::Analysis(AnalysisError::ArgumentCountMismatch { .. }))
+    ));
+}
+
+#[test]
+fn runaway_recursion_is_a_runtime_error() {
+    let result = run("
+        function forever(n: int): int {
+            return forever(n + 1);
+        }
+        forever(0);
+    ");
+    let Err(Error::Analysis(error)) = result else {
+        panic!("expected a runtime error");
+    };
+    assert!(matches!(error, AnalysisError::CallDepthExceeded { .. }));
+    assert!(error.is_runtime());
+}
+
+#[test]
+fn deep_recursion_below_the_limit_works() {
+    let output = run("
+        function count(n: int): int {
+            if n == 0 {
+                return 0;
+            }
+            return 1 + count(n - 1);
+        }
+        println(inttostr(count(500)));
+    ");
+    assert_eq!(output.unwrap(), "500\n");
+}
+
+#[test]
+fn parameters_must_have_distinct_names() {
+    let result = run("
+        function f(a: int, a: string) {
+            println(a);
+        }
+    ");
+    let Err(Error::Parse(errors)) = result else {
+        panic!("expected a parse error");
+    };
+    assert!(matches!(
+        &errors[..],
+        [ParseError::DuplicateParameter { name, .. }] if name == "a"
+    ));
+}
+
+#[test]
+fn functions_can_call_each_other() {
+    let output = run("
+        function is_even(n: int): bool {
+            if n == 0 {
+                return true;
+            }
+            return is_odd(n - 1);
+        }
+        function is_odd(n: int): bool {
+            if n == 0 {
+                return false;
+            }
+            return is_even(n - 1);
+        }
+        if is_even(10) && is_odd(7) {
+            println(\"ok\");
+        }
+    ");
+    assert_eq!(output.unwrap(), "ok\n");
+}
+
+#[test]
+fn functions_can_be_called_before_their_declaration() {
+    let output = run("
+        println(greeting(\"amy\"));
+        function greeting(name: string): string {
+            return \"hi \" + name;
+        }
+    ");
+    assert_eq!(output.unwrap(), "hi amy\n");
+}
+
+#[test]
+fn loop_bodies_can_declare_functions() {
+    let output = run("
+        let i = 0;
+        while i < 2 {
+            function g(): int {
+                return 1;
+            }
+            i = i + g();
+        }
+        println(inttostr(i));
+    ");
+    assert_eq!(output.unwrap(), "2\n");
+}
+
+#[test]
+fn recursive_functions_can_declare_functions() {
+    let output = run("
+        function fact(n: int): int {
+            function one(): int {
+                return 1;
+            }
+            if n <= one() {
+                return one();
+            }
+            return n * fact(n - 1);
+        }
+        println(inttostr(fact(5)));
+    ");
+    assert_eq!(output.unwrap(), "120\n");
+}
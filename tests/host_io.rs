@@ -0,0 +1,31 @@
+use aryalang::{Aryalang, StreamIo};
+use std::io::Cursor;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+#[test]
+fn interpreter_can_run_on_another_thread() {
+    let io = Arc::new(Mutex::new(StreamIo::new(
+        Cursor::new(b"amy\n".to_vec()),
+        Vec::new(),
+    )));
+    let mut interpreter = Aryalang::with_io(
+        "let name = input(); print(\"hi \" + name);".to_string(),
+        io.clone(),
+    );
+
+    let handle = thread::spawn(move || interpreter.try_run().is_ok());
+    assert!(handle.join().unwrap());
+    let output = io.lock().unwrap().writer.clone();
+    assert_eq!(String::from_utf8(output).unwrap(), "hi amy\n");
+}
+
+#[test]
+fn run_writes_errors_to_the_given_output() {
+    let source = "println(\"never\"); let x: int = \"one\";";
+    let io = Arc::new(Mutex::new(StreamIo::new(Cursor::new(vec![]), Vec::new())));
+    Aryalang::with_io(source.to_string(), io.clone()).run();
+    let output = String::from_utf8(io.lock().unwrap().writer.clone()).unwrap();
+    let error = Aryalang::new(source.to_string()).try_run().unwrap_err();
+    assert_eq!(output, format!("{}\n", error));
+}
@@ -0,0 +1,222 @@
+use aryalang::core::lexer::definition::LexError;
+use aryalang::core::token::{Token, TokenKind};
+use aryalang::core::Lexer;
+use aryalang::{Aryalang, Error};
+
+fn tokenize(source: &str) -> Result<Vec<Token>, LexError> {
+    Lexer::new(source).tokenize()
+}
+
+fn kinds(source: &str) -> Vec<TokenKind> {
+    tokenize(source)
+        .unwrap()
+        .into_iter()
+        .map(|token| token.kind)
+        .collect()
+}
+
+#[test]
+fn comments_are_dropped() {
+    assert_eq!(
+        kinds("let x = 1; // the answer\n/* a /* nested */ comment */ x"),
+        kinds("let x = 1; x")
+    );
+    // A comment ends the token before it.
+    assert_eq!(kinds("x// y"), kinds("x"));
+    assert_eq!(kinds("x/**/y"), kinds("x y"));
+}
+
+#[test]
+fn comments_keep_line_numbers() {
+    let tokens = tokenize("// one\n/* two\nthree */ let\n  x").unwrap();
+    let positions: Vec<(usize, usize)> = tokens
+        .iter()
+        .map(|token| (token.span.line, token.span.column))
+        .collect();
+    assert_eq!(positions, vec![(3, 10), (4, 3)]);
+}
+
+#[test]
+fn unterminated_block_comment_is_an_error() {
+    let Err(LexError::UnterminatedComment { span }) = tokenize("let x;\n/* a /* b */") else {
+        panic!("expected an unterminated comment");
+    };
+    assert_eq!((span.line, span.column), (2, 1));
+}
+
+// The value of the only string literal in the source.
+fn string(source: &str) -> String {
+    match &kinds(source)[..] {
+        [TokenKind::String(value)] => value.clone(),
+        kinds => panic!("expected a single string, found {:?}", kinds),
+    }
+}
+
+#[test]
+fn escape_sequences_are_replaced() {
+    assert_eq!(
+        string(r#""tab\there\nquote\" slash\\ nul\0 cr\r""#),
+        "tab\there\nquote\" slash\\ nul\0 cr\r"
+    );
+}
+
+#[test]
+fn unicode_escapes_take_hex_code_points() {
+    assert_eq!(string(r#""\u{41}\u{e9}\u{1F600}""#), "Aé😀");
+}
+
+#[test]
+fn raw_strings_are_not_escaped() {
+    assert_eq!(string(r#"r"C:\path\n""#), r"C:\path\n");
+    assert_eq!(string(r###"r#"say "hi""#"###), r#"say "hi""#);
+    assert_eq!(string(r###"r##"a "# b"##"###), r##"a "# b"##);
+}
+
+#[test]
+fn bad_escapes_are_errors() {
+    for source in [
+        r#""\q""#,
+        r#""\u41""#,
+        r#""\u{}""#,
+        r#""\u{110000}""#,
+        r#""\u{D800}""#,
+        r#""\u{41"#,
+    ] {
+        assert!(
+            matches!(tokenize(source), Err(LexError::InvalidEscape { .. })),
+            "{}",
+            source
+        );
+    }
+    let Err(LexError::InvalidEscape { message, .. }) = tokenize(r#""\q""#) else {
+        panic!("expected an invalid escape");
+    };
+    assert_eq!(message, r"unknown escape sequence \q");
+}
+
+// The error for the source, along with where it starts as (line, column).
+fn error_at(source: &str) -> (LexError, (usize, usize)) {
+    let error = tokenize(source).unwrap_err();
+    let span = error.span();
+    (error, (span.line, span.column))
+}
+
+#[test]
+fn every_lexical_error_has_a_location() {
+    assert!(matches!(
+        error_at("let s = \"open"),
+        (LexError::UnterminatedString { .. }, (1, 9))
+    ));
+    assert!(matches!(
+        error_at("let s = r#\"open\""),
+        (LexError::UnterminatedString { .. }, (1, 9))
+    ));
+    assert!(matches!(
+        error_at("x;\n  /* open"),
+        (LexError::UnterminatedComment { .. }, (2, 3))
+    ));
+    assert!(matches!(
+        error_at("let x = 1;\nlet y = x @ 2;"),
+        (LexError::InvalidCharacter { character: '@', .. }, (2, 11))
+    ));
+    assert!(matches!(
+        error_at("let a#b = 1;"),
+        (LexError::InvalidCharacter { character: '#', .. }, (1, 6))
+    ));
+    assert!(matches!(
+        error_at("let s = \"a\\xb\";"),
+        (LexError::InvalidEscape { .. }, (1, 11))
+    ));
+    assert!(matches!(
+        error_at("let n =\n 1.2.3;"),
+        (LexError::MalformedNumber { literal, .. }, (2, 2)) if literal == "1.2.3"
+    ));
+    assert!(matches!(
+        error_at("let n = 99999999999999999999;"),
+        (LexError::NumberOutOfRange { .. }, (1, 9))
+    ));
+}
+
+#[test]
+fn lexical_errors_stop_the_script() {
+    let error = Aryalang::new("println(\"never\"); let x = $;".to_string())
+        .try_run()
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        Error::Lex(LexError::InvalidCharacter { character: '$', .. })
+    ));
+}
+
+fn number(value: &str) -> TokenKind {
+    TokenKind::Number(value.to_string())
+}
+
+#[test]
+fn integers_can_be_written_in_other_bases() {
+    assert_eq!(
+        kinds("0xFF 0Xff 0b1010 0o17 0 007"),
+        vec![
+            number("255"),
+            number("255"),
+            number("10"),
+            number("15"),
+            number("0"),
+            number("7")
+        ]
+    );
+}
+
+#[test]
+fn separators_go_between_digits() {
+    assert_eq!(
+        kinds("1_000_000 0xFF_FF 1_000.000_1"),
+        vec![number("1000000"), number("65535"), number("1000.0001")]
+    );
+    for source in ["1__0", "1_", "0x_FF", "1_.5", "1._5"] {
+        assert!(
+            matches!(tokenize(source), Err(LexError::MalformedNumber { .. })),
+            "{}",
+            source
+        );
+    }
+}
+
+#[test]
+fn decimals_can_have_an_exponent() {
+    assert_eq!(
+        kinds("1.5e3 2E-3 1e+2"),
+        vec![number("1.5e3"), number("2E-3"), number("1e+2")]
+    );
+    // Hex digits include e, so this is a subtraction.
+    assert_eq!(
+        kinds("0x1e-3"),
+        vec![number("30"), TokenKind::Minus, number("3")]
+    );
+}
+
+#[test]
+fn malformed_numbers_are_errors() {
+    for source in ["0x", "0b102", "0o8", "1e", "1.", "1.2.3", "12ab", "1e5.0"] {
+        assert!(
+            matches!(tokenize(source), Err(LexError::MalformedNumber { .. })),
+            "{}",
+            source
+        );
+    }
+}
+
+#[test]
+fn numbers_must_fit() {
+    assert_eq!(
+        kinds("9223372036854775807 0x7FFFFFFFFFFFFFFF"),
+        vec![number("9223372036854775807"), number("9223372036854775807")]
+    );
+    for source in ["9223372036854775808", "0x8000000000000000", "1e400"] {
+        assert!(
+            matches!(tokenize(source), Err(LexError::NumberOutOfRange { .. })),
+            "{}",
+            source
+        );
+    }
+}
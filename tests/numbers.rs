@@ -0,0 +1,255 @@
+mod common;
+
+use aryalang::core::semantic_analyzer::defination::AnalysisError;
+use aryalang::Error;
+use common::run;
+
+#[test]
+fn integers_are_64_bit() {
+    let output = run("
+        let big = 3000000000;
+        println(inttostr(big * 3));
+    ");
+    assert_eq!(output.unwrap(), "9000000000\n");
+}
+
+#[test]
+fn mixed_operands_are_promoted_to_decimals() {
+    let output = run("
+        println(floattostr(1 + 2.5));
+        println(floattostr(5 / 2.0));
+        if 1 < 1.5 {
+            println(\"less\");
+        }
+        if 2 == 2.0 {
+            println(\"equal\");
+        }
+    ");
+    assert_eq!(output.unwrap(), "3.5\n2.5\nless\nequal\n");
+}
+
+#[test]
+fn integers_widen_where_a_decimal_is_expected() {
+    let output = run("
+        function half(n: float): float {
+            return n / 2;
+        }
+        let total: float = 1;
+        total = total + half(3);
+        println(floattostr(total));
+    ");
+    assert_eq!(output.unwrap(), "2.5\n");
+}
+
+#[test]
+fn decimals_do_not_narrow_to_integers() {
+    let result = run("
+        let count = 1;
+        count = 2.5;
+    ");
+    assert!(matches!(
+        result,
+        Err(Error::Analysis(AnalysisError::IllegalOperation { .. }))
+    ));
+}
+
+#[test]
+fn int_and_float_convert_explicitly() {
+    let output = run("
+        println(inttostr(int(2.9)));
+        println(inttostr(int(0 - 2.9)));
+        println(inttostr(int(7)));
+        println(floattostr(float(3) / 2));
+    ");
+    assert_eq!(output.unwrap(), "2\n-2\n7\n1.5\n");
+}
+
+#[test]
+fn int_rejects_a_string() {
+    assert!(matches!(
+        run("let n = int(\"3\");"),
+        Err(Error::Analysis(AnalysisError::ArgumentTypeMismatch { .. }))
+    ));
+}
+
+#[test]
+fn integer_overflow_is_a_runtime_error() {
+    let result = run("
+        let largest = 9223372036854775807;
+        println(inttostr(largest + 1));
+    ");
+    assert!(matches!(
+        result,
+        Err(Error::Analysis(AnalysisError::IntegerOverflow { ref operation, .. })) if operation == "+"
+    ));
+    assert!(matches!(
+        run("let n = int(100000000000000000000.0);"),
+        Err(Error::Analysis(AnalysisError::IntegerOverflow { .. }))
+    ));
+}
+
+#[test]
+fn integer_division_by_zero_is_a_runtime_error() {
+    let result = run("
+        let zero = 0;
+        println(\"before\");
+        println(inttostr(10 / zero));
+    ");
+    let Err(Error::Analysis(error)) = result else {
+        panic!("expected a runtime error");
+    };
+    assert!(matches!(error, AnalysisError::DivisionByZero { .. }));
+    assert!(error.is_runtime());
+    assert_eq!(error.span().line, 4);
+}
+
+#[test]
+fn overflow_builtins_pick_their_own_behaviour() {
+    let output = run("
+        let largest = 9223372036854775807;
+        println(inttostr(wrapping_add(largest, 1)));
+        println(inttostr(saturating_mul(largest, 2)));
+        println(inttostr(checked_mul(largest, 2, -1)));
+        println(inttostr(checked_div(5, 0, 0)));
+        println(inttostr(checked_sub(5, 2, 0)));
+    ");
+    assert_eq!(
+        output.unwrap(),
+        "-9223372036854775808\n9223372036854775807\n-1\n0\n3\n"
+    );
+}
+
+#[test]
+fn modulo_and_power() {
+    let output = run("
+        println(inttostr(7 % 3));
+        println(inttostr(-7 % 3));
+        println(floattostr(7.5 % 2));
+        println(inttostr(2 ** 10));
+        println(floattostr(2 ** -1.0));
+    ");
+    assert_eq!(output.unwrap(), "1\n-1\n1.5\n1024\n0.5\n");
+}
+
+#[test]
+fn bitwise_operators_work_on_integers() {
+    let output = run("
+        println(inttostr(12 & 10));
+        println(inttostr(12 | 10));
+        println(inttostr(12 ^ 10));
+        println(inttostr(~0));
+        println(inttostr(1 << 10));
+        println(inttostr(-16 >> 2));
+    ");
+    assert_eq!(output.unwrap(), "8\n14\n6\n-1\n1024\n-4\n");
+}
+
+#[test]
+fn bitwise_operators_reject_decimals() {
+    assert!(matches!(
+        run("let n = 1.5 & 1;"),
+        Err(Error::Analysis(AnalysisError::IllegalOperation { .. }))
+    ));
+}
+
+#[test]
+fn remainder_and_power_errors_are_runtime_errors() {
+    assert!(matches!(
+        run("let zero = 0; let n = 5 % zero;"),
+        Err(Error::Analysis(AnalysisError::DivisionByZero { .. }))
+    ));
+    assert!(matches!(
+        run("let n = 2 ** -1;"),
+        Err(Error::Analysis(AnalysisError::NegativeExponent { .. }))
+    ));
+    assert!(matches!(
+        run("let n = 2 ** 63;"),
+        Err(Error::Analysis(AnalysisError::IntegerOverflow { .. }))
+    ));
+    assert!(matches!(
+        run("let n = 1 << 64;"),
+        Err(Error::Analysis(AnalysisError::IntegerOverflow { .. }))
+    ));
+}
+
+#[test]
+fn arrays_mixing_integers_and_decimals_hold_decimals() {
+    let output = run("
+        let a = [1, 2.5];
+        println(floattostr(a[0] + a[1]));
+        let grid = [[1], [2.5]];
+        let row = grid[0];
+        println(floattostr(row[0]));
+        if [1, 2] == [1.0, 2.0] {
+            println(\"equal\");
+        }
+    ");
+    assert_eq!(output.unwrap(), "3.5\n1\nequal\n");
+}
+
+#[test]
+fn integer_arrays_widen_where_decimal_arrays_are_expected() {
+    let output = run("
+        function total(values: [float]): float {
+            return values[0] + values[1];
+        }
+        let a: [float] = [1, 2];
+        a[2] = 3;
+        println(floattostr(a[0] / 2 + a[2]));
+        println(floattostr(total([1, 2])));
+    ");
+    assert_eq!(output.unwrap(), "3.5\n3\n");
+}
+
+#[test]
+fn decimal_arrays_do_not_narrow_to_integer_arrays() {
+    assert!(matches!(
+        run("let a: [int] = [1, 2.5];"),
+        Err(Error::Analysis(AnalysisError::MismatchedTypes { .. }))
+    ));
+}
+
+#[test]
+fn unary_plus_leaves_numbers_unchanged() {
+    let output = run("
+        let x = +5;
+        let y = +2.5;
+        println(inttostr(x - +2));
+        println(floattostr(y));
+    ");
+    assert_eq!(output.unwrap(), "3\n2.5\n");
+    assert!(matches!(
+        run("let s = +\"five\";"),
+        Err(Error::Analysis(AnalysisError::IllegalOperation { .. }))
+    ));
+}
+
+#[test]
+fn strings_convert_to_numbers() {
+    let output = run("
+        println(inttostr(strtoint(\" 42 \") + 1));
+        println(floattostr(strtofloat(\"1.5\")));
+    ");
+    assert_eq!(output.unwrap(), "43\n1.5\n");
+}
+
+#[test]
+fn strings_that_are_not_numbers_are_a_runtime_error() {
+    let result = run("let n = strtoint(\"forty\");");
+    let Err(Error::Analysis(error)) = result else {
+        panic!("expected a runtime error");
+    };
+    assert!(matches!(
+        error,
+        AnalysisError::InvalidNumber { ref found, .. } if found == "forty"
+    ));
+    assert!(error.is_runtime());
+    assert!(matches!(
+        run("let n = strtoint(\"1.5\");"),
+        Err(Error::Analysis(AnalysisError::InvalidNumber { .. }))
+    ));
+    assert!(matches!(
+        run("let n = strtofloat(\"\");"),
+        Err(Error::Analysis(AnalysisError::InvalidNumber { .. }))
+    ));
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use aryalang::core::semantic_analyzer::defination::AnalysisError;
+use aryalang::Error;
+use common::run;
+
+#[test]
+fn and_and_or_skip_the_right_operand_when_the_left_decides() {
+    // t and f print their name, to tell which operands were evaluated.
+    let output = run("
+        function t(): bool {
+            print(\"t\");
+            return true;
+        }
+        function f(): bool {
+            print(\"f\");
+            return false;
+        }
+        if f() && t() { }
+        println(\"\");
+        if t() || f() { }
+        println(\"\");
+        if t() && f() { }
+        println(\"\");
+        if f() || t() { }
+        println(\"\");
+    ");
+    assert_eq!(output.unwrap(), "f\nt\ntf\nft\n");
+}
+
+#[test]
+fn skipped_operands_cannot_fail() {
+    let output = run("
+        let zero = 0;
+        if false && 1 / zero == 0 {
+            println(\"wrong\");
+        }
+        if true || 1 / zero == 0 {
+            println(\"ok\");
+        }
+    ");
+    assert_eq!(output.unwrap(), "ok\n");
+}
+
+#[test]
+fn logical_operators_need_booleans() {
+    for source in [
+        "let x = 1 && true;",
+        "let x = true || \"yes\";",
+        "let x = !1;",
+    ] {
+        assert!(
+            matches!(
+                run(source),
+                Err(Error::Analysis(AnalysisError::IllegalOperation { .. }))
+            ),
+            "{}",
+            source
+        );
+    }
+}
+
+#[test]
+fn not_equals_works_on_every_comparable_type() {
+    let output = run("
+        function show(value: bool) {
+            if value {
+                print(\"t\");
+            } else {
+                print(\"f\");
+            }
+        }
+        show(1 != 2);
+        show(1.5 != 1.5);
+        show(1 != 1.0);
+        show(\"a\" != \"b\");
+        show(true != true);
+        show([1, 2] != [1, 3]);
+        show([[1], [2]] != [[1], [2.0]]);
+    ");
+    assert_eq!(output.unwrap(), "tfftftf");
+}
+
+#[test]
+fn values_of_different_types_cannot_be_compared() {
+    for source in [
+        "let x = 1 != \"1\";",
+        "let x = true == 1;",
+        "let x = [1] != 1;",
+    ] {
+        assert!(
+            matches!(
+                run(source),
+                Err(Error::Analysis(AnalysisError::IllegalOperation { .. }))
+            ),
+            "{}",
+            source
+        );
+    }
+}
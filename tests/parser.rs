@@ -0,0 +1,151 @@
+use aryalang::core::parser::ast::{Expression, Statement};
+use aryalang::core::{Lexer, Parser};
+
+// Renders an expression as an s-expression, so that the tests can spell out how it was grouped
+// without caring about spans.
+fn render(expression: &Expression) -> String {
+    match expression {
+        Expression::BinOp(left, op, right, _, _) => {
+            format!("({} {} {})", op, render(left), render(right))
+        }
+        Expression::UnaryOp(op, operand, _, _) => format!("({} {})", op, render(operand)),
+        Expression::Identifier(name, _, _) => name.clone(),
+        Expression::Number(number, _, _) => number.clone(),
+        Expression::StringLiteral(value, _, _) => format!("{:?}", value),
+        Expression::Boolean(value, _, _) => value.to_string(),
+        Expression::FunctionCall(name, args, _, _) => {
+            let args: Vec<String> = args.iter().map(render).collect();
+            format!("{}({})", name, args.join(" "))
+        }
+        Expression::Array(elements, _, _) => {
+            let elements: Vec<String> = elements.iter().map(render).collect();
+            format!("[{}]", elements.join(" "))
+        }
+        Expression::ArrayAccess(name, index, _, _) => format!("{}[{}]", name, render(index)),
+    }
+}
+
+fn parse(source: &str) -> Result<Vec<Statement>, String> {
+    let tokens = Lexer::new(source).tokenize().map_err(|e| e.to_string())?;
+    Parser::new(&tokens)
+        .parse()
+        .map_err(|errors| errors[0].to_string())
+}
+
+fn parse_expression(source: &str) -> String {
+    let statements = parse(source).unwrap();
+    match &statements[..] {
+        [Statement::ExpressionStatement(expression, _)]
+        | [Statement::Assignment(_, expression, _)] => render(expression),
+        _ => panic!("expected a single expression in {:?}", source),
+    }
+}
+
+#[test]
+fn multiplication_binds_tighter_than_addition() {
+    assert_eq!(parse_expression("1 + 2 * 3;"), "(+ 1 (* 2 3))");
+    assert_eq!(parse_expression("1 * 2 + 3;"), "(+ (* 1 2) 3)");
+    assert_eq!(parse_expression("1 - 6 / 3;"), "(- 1 (/ 6 3))");
+}
+
+#[test]
+fn arithmetic_is_left_associative() {
+    assert_eq!(parse_expression("1 - 2 - 3;"), "(- (- 1 2) 3)");
+    assert_eq!(parse_expression("8 / 4 / 2;"), "(/ (/ 8 4) 2)");
+    assert_eq!(parse_expression("1 + 2 - 3 + 4;"), "(+ (- (+ 1 2) 3) 4)");
+}
+
+#[test]
+fn comparison_binds_tighter_than_equality() {
+    assert_eq!(parse_expression("a < b + 1 == c;"), "(== (< a (+ b 1)) c)");
+    assert_eq!(parse_expression("a == b < c;"), "(== a (< b c))");
+    assert_eq!(parse_expression("a != b >= c;"), "(!= a (>= b c))");
+}
+
+#[test]
+fn logical_operators_bind_looser_than_comparisons() {
+    assert_eq!(
+        parse_expression("a > 0 && a < 10;"),
+        "(&& (> a 0) (< a 10))"
+    );
+    assert_eq!(parse_expression("a || b && c;"), "(|| a (&& b c))");
+    assert_eq!(parse_expression("a && b || c;"), "(|| (&& a b) c)");
+    assert_eq!(parse_expression("a || b || c;"), "(|| (|| a b) c)");
+}
+
+#[test]
+fn assignment_is_right_associative_and_loosest() {
+    assert_eq!(parse_expression("a = b = 1;"), "(= a (= b 1))");
+    assert_eq!(parse_expression("a = b || c;"), "(= a (|| b c))");
+    assert_eq!(parse_expression("a = 1 + 2 * 3;"), "(= a (+ 1 (* 2 3)))");
+}
+
+#[test]
+fn unary_operators_bind_tightest() {
+    assert_eq!(parse_expression("-a * b;"), "(* (- a) b)");
+    assert_eq!(parse_expression("!a == b;"), "(== (! a) b)");
+    assert_eq!(parse_expression("!!a;"), "(! (! a))");
+    assert_eq!(parse_expression("-1 - -2;"), "(- (- 1) (- 2))");
+}
+
+#[test]
+fn power_is_right_associative_and_binds_tighter_than_unary_operators() {
+    assert_eq!(parse_expression("2 ** 3 ** 2;"), "(** 2 (** 3 2))");
+    assert_eq!(parse_expression("-a ** 2;"), "(- (** a 2))");
+    assert_eq!(parse_expression("a ** -b;"), "(** a (- b))");
+    assert_eq!(parse_expression("2 * a ** 2;"), "(* 2 (** a 2))");
+}
+
+#[test]
+fn modulo_binds_like_multiplication() {
+    assert_eq!(parse_expression("a + b % c;"), "(+ a (% b c))");
+    assert_eq!(parse_expression("a % b * c;"), "(* (% a b) c)");
+}
+
+#[test]
+fn bitwise_operators_bind_between_comparisons_and_arithmetic() {
+    assert_eq!(parse_expression("a & 1 == 0;"), "(== (& a 1) 0)");
+    assert_eq!(parse_expression("a | b ^ c & d;"), "(| a (^ b (& c d)))");
+    assert_eq!(parse_expression("a & b << 1;"), "(& a (<< b 1))");
+    assert_eq!(parse_expression("1 << a + 1;"), "(<< 1 (+ a 1))");
+    assert_eq!(parse_expression("a >> 1 >> 2;"), "(>> (>> a 1) 2)");
+    assert_eq!(parse_expression("~a & b;"), "(& (~ a) b)");
+    assert_eq!(parse_expression("a && b & c;"), "(&& a (& b c))");
+}
+
+#[test]
+fn parentheses_override_precedence() {
+    assert_eq!(parse_expression("(1 + 2) * 3;"), "(* (+ 1 2) 3)");
+    assert_eq!(parse_expression("1 - (2 - 3);"), "(- 1 (- 2 3))");
+    assert_eq!(parse_expression("-(a + b);"), "(- (+ a b))");
+}
+
+#[test]
+fn operands_can_be_calls_and_array_accesses() {
+    assert_eq!(
+        parse_expression("f(1 + 2, x) * a[i + 1];"),
+        "(* f((+ 1 2) x) a[(+ i 1)])"
+    );
+    assert_eq!(parse_expression("[1 + 2, 3] == b;"), "(== [(+ 1 2) 3] b)");
+}
+
+#[test]
+fn juxtaposition_is_not_multiplication() {
+    assert!(parse("2(3);").is_err());
+    assert!(parse("let x = 2 (1 + 1);").is_err());
+    assert!(parse("f(2(3));").is_err());
+}
+
+#[test]
+fn recovers_after_errors_and_keeps_the_rest_of_the_ast() {
+    let source = "let a = ;\nlet b = 1;\nif b > { let c = 2; }\nlet d = * 2;\nb = 3;";
+    let tokens = Lexer::new(source).tokenize().unwrap();
+    let (statements, errors) = Parser::new(&tokens).parse_partial();
+
+    let lines: Vec<usize> = errors.iter().map(|error| error.span().line).collect();
+    assert_eq!(lines, vec![1, 3, 4]);
+    assert!(matches!(
+        &statements[..],
+        [Statement::Let(b, ..), Statement::Assignment(..)] if b == "b"
+    ));
+}
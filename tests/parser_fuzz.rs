@@ -0,0 +1,190 @@
+// Feeds the lexer and the parser with a lot of random and broken input. Whatever the input, they
+// have to return an error instead of panicking. The inputs come from a fixed seed, so a failure
+// always reproduces.
+
+use aryalang::core::parser::definition::{ParseError, MAX_NESTING};
+use aryalang::core::{Lexer, Parser};
+use std::panic;
+
+// xorshift64*, good enough to shuffle tokens around without pulling in a dependency.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+
+    fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[self.below(items.len())]
+    }
+}
+
+const FRAGMENTS: &[&str] = &[
+    "let", "function", "if", "else", "return", "true", "false", "for", "while", "break",
+    "continue", "x", "y", "f", "int", "string", "[int]", "0", "1", "42", "0xFF", "1.5", "\"s\"",
+    "=", "+", "-", "!", "*", "/", "==", "!=", "<", ">", "<=", ">=", "&&", "||", ";", ":", ",", "(",
+    ")", "{", "}", "[", "]",
+];
+
+const PROGRAMS: &[&str] = &[
+    "let x = 1 + 2 * 3;",
+    "let a = [1, 2, 3]; a[1] = 5; println(inttostr(a[1]));",
+    "if x > 1 && !(y == 2) { println(\"a\"); } else if x < 0 { x = 0; } else { x = 1; }",
+    "for (i = 0; i < 10; i = i + 1) { if i == 3 { continue; } print(inttostr(i)); }",
+    "while x < 10 { x = x + 1; if x > 5 { break; } }",
+    "function fib(n: int): int { if n < 2 { return n; } return fib(n - 1) + fib(n - 2); }",
+    "function greet(name: string, times: [int]) { println(\"hi \" + name); }",
+];
+
+// The first source that makes the lexer or the parser panic, if any.
+fn first_panicking(sources: impl IntoIterator<Item = String>) -> Option<String> {
+    // Otherwise the message of every caught panic would be printed.
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let found = sources.into_iter().find(|source| {
+        panic::catch_unwind(|| {
+            if let Ok(tokens) = Lexer::new(source).tokenize() {
+                let _ = Parser::new(&tokens).parse();
+            }
+        })
+        .is_err()
+    });
+    panic::set_hook(hook);
+    found
+}
+
+#[test]
+fn random_token_soup_never_panics() {
+    let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+    let sources = (0..20_000).map(move |_| {
+        let length = 1 + rng.below(30);
+        let fragments: Vec<&str> = (0..length).map(|_| rng.pick(FRAGMENTS)).collect();
+        fragments.join(" ")
+    });
+    assert_eq!(first_panicking(sources), None);
+}
+
+#[test]
+fn every_prefix_of_a_valid_program_never_panics() {
+    let sources = PROGRAMS.iter().flat_map(|program| {
+        program
+            .char_indices()
+            .map(move |(index, _)| program[..index].to_string())
+    });
+    assert_eq!(first_panicking(sources), None);
+}
+
+#[test]
+fn mutated_programs_never_panic() {
+    let mut rng = Rng(0xD1B5_4A32_D192_ED03);
+    let sources = (0..20_000).map(move |_| {
+        let mut fragments: Vec<&str> = rng.pick(PROGRAMS).split(' ').collect();
+        for _ in 0..1 + rng.below(3) {
+            let index = rng.below(fragments.len());
+            match rng.below(3) {
+                0 if fragments.len() > 1 => {
+                    fragments.remove(index);
+                }
+                1 => fragments.insert(index, rng.pick(FRAGMENTS)),
+                _ => fragments[index] = rng.pick(FRAGMENTS),
+            }
+        }
+        fragments.join(" ")
+    });
+    assert_eq!(first_panicking(sources), None);
+}
+
+#[test]
+fn random_characters_never_panic() {
+    let alphabet: Vec<char> = "abc019_ \n\t\"\\#r.;:,(){}[]=+-*/!<>&|@$%é漢"
+        .chars()
+        .collect();
+    let mut rng = Rng(0x2545_F491_4F6C_DD1D);
+    let sources = (0..20_000).map(move |_| {
+        let length = rng.below(24);
+        (0..length)
+            .map(|_| alphabet[rng.below(alphabet.len())])
+            .collect()
+    });
+    assert_eq!(first_panicking(sources), None);
+}
+
+// The errors from parsing the source. Unlike the tests above, these run on the test thread, so
+// an input that nests too deeply for the parser overflows the stack and fails the whole run.
+fn parse_errors(source: &str) -> Vec<ParseError> {
+    let tokens = Lexer::new(source).tokenize().unwrap();
+    Parser::new(&tokens).parse().err().unwrap_or_default()
+}
+
+fn is_too_deeply_nested(errors: &[ParseError]) -> bool {
+    matches!(errors, [ParseError::TooDeeplyNested { .. }])
+}
+
+#[test]
+fn deeply_nested_parentheses_are_an_error() {
+    let source = format!("let x = {}1{};", "(".repeat(2_000), ")".repeat(2_000));
+    assert!(is_too_deeply_nested(&parse_errors(&source)));
+}
+
+#[test]
+fn long_runs_of_unary_operators_are_an_error() {
+    let source = format!("let x = {}1;", "-".repeat(100_000));
+    assert!(is_too_deeply_nested(&parse_errors(&source)));
+    let source = format!("let x = {}true;", "!".repeat(100_000));
+    assert!(is_too_deeply_nested(&parse_errors(&source)));
+}
+
+#[test]
+fn long_right_associative_chains_are_an_error() {
+    let source = format!("let x = 2{};", " ** 2".repeat(10_000));
+    assert!(is_too_deeply_nested(&parse_errors(&source)));
+}
+
+#[test]
+fn deeply_nested_arrays_are_an_error() {
+    let source = format!("let x = {}1{};", "[".repeat(10_000), "]".repeat(10_000));
+    assert!(is_too_deeply_nested(&parse_errors(&source)));
+}
+
+#[test]
+fn deeply_nested_type_annotations_are_an_error() {
+    let source = format!(
+        "let x: {}int{} = [];",
+        "[".repeat(200_000),
+        "]".repeat(200_000)
+    );
+    assert!(is_too_deeply_nested(&parse_errors(&source)));
+}
+
+#[test]
+fn deeply_nested_blocks_are_an_error() {
+    let source = format!("{}x = 1;{}", "if true {".repeat(20_000), "}".repeat(20_000));
+    assert!(is_too_deeply_nested(&parse_errors(&source)));
+    let source = format!(
+        "{}x = 1;{}",
+        "while true {".repeat(20_000),
+        "}".repeat(20_000)
+    );
+    assert!(is_too_deeply_nested(&parse_errors(&source)));
+}
+
+#[test]
+fn nesting_up_to_the_limit_parses() {
+    // The deepest expression allowed, inside of the deepest block allowed.
+    let depth = MAX_NESTING - 1;
+    let expression = format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+    let source = format!(
+        "{}let x = {};{}",
+        "if true {".repeat(MAX_NESTING),
+        expression,
+        "}".repeat(MAX_NESTING)
+    );
+    assert!(parse_errors(&source).is_empty());
+}
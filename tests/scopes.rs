@@ -0,0 +1,183 @@
+mod common;
+
+use aryalang::core::semantic_analyzer::defination::AnalysisError;
+use aryalang::Error;
+use common::run;
+
+#[test]
+fn while_body_declares_a_fresh_variable_on_every_iteration() {
+    let output = run("
+        let i = 0;
+        while i < 3 {
+            let square = i * i;
+            println(inttostr(square));
+            i = i + 1;
+        }
+    ");
+    assert_eq!(output.unwrap(), "0\n1\n4\n");
+}
+
+#[test]
+fn for_body_declares_a_fresh_variable_on_every_iteration() {
+    let output = run("
+        let i = 0;
+        let total = 0;
+        for (i = 0; i < 4; i = i + 1) {
+            let doubled = i * 2;
+            total = total + doubled;
+        }
+        println(inttostr(total));
+    ");
+    assert_eq!(output.unwrap(), "12\n");
+}
+
+#[test]
+fn nested_loops_declare_variables() {
+    let output = run("
+        let i = 0;
+        while i < 2 {
+            let row = \"\";
+            let j = 0;
+            while j < 3 {
+                let cell = inttostr(i * 3 + j);
+                row = row + cell;
+                j = j + 1;
+            }
+            println(row);
+            i = i + 1;
+        }
+    ");
+    assert_eq!(output.unwrap(), "012\n345\n");
+}
+
+#[test]
+fn break_and_continue_drop_the_locals_of_the_loop_body() {
+    let output = run("
+        let i = 0;
+        while true {
+            i = i + 1;
+            let odd = i == 1 || i == 3;
+            if odd {
+                continue;
+            }
+            let message = \"even \" + inttostr(i);
+            println(message);
+            if i == 4 {
+                break;
+            }
+        }
+    ");
+    assert_eq!(output.unwrap(), "even 2\neven 4\n");
+}
+
+#[test]
+fn loop_locals_do_not_leak_out_of_the_loop() {
+    let result = run("
+        let i = 0;
+        while i < 3 {
+            let last = i;
+            i = i + 1;
+        }
+        println(inttostr(last));
+    ");
+    assert!(matches!(
+        result,
+        Err(Error::Analysis(AnalysisError::UndefinedVariable { ref expected, .. })) if expected == "last"
+    ));
+}
+
+#[test]
+fn block_shadows_a_variable_of_an_enclosing_block() {
+    let output = run("
+        let name = \"outer\";
+        if true {
+            let name = \"inner\";
+            println(name);
+        }
+        println(name);
+    ");
+    assert_eq!(output.unwrap(), "inner\nouter\n");
+}
+
+#[test]
+fn declaring_a_variable_twice_in_the_same_block_is_an_error() {
+    let result = run("
+        while true {
+            let a = 1;
+            let a = 2;
+            break;
+        }
+    ");
+    assert!(matches!(
+        result,
+        Err(Error::Analysis(
+            AnalysisError::VariableAlreadyDefined { .. }
+        ))
+    ));
+}
+
+#[test]
+fn function_locals_are_dropped_between_calls() {
+    let output = run("
+        function count_up(limit: int): int {
+            let total = 0;
+            let i = 0;
+            while i < limit {
+                let step = 1;
+                total = total + step;
+                i = i + 1;
+            }
+            return total;
+        }
+        println(inttostr(count_up(2)));
+        println(inttostr(count_up(3)));
+    ");
+    assert_eq!(output.unwrap(), "2\n3\n");
+}
+
+#[test]
+fn functions_see_and_update_global_variables() {
+    let output = run("
+        let greeting = \"hi \";
+        let calls = 0;
+        function greet(name: string): string {
+            calls = calls + 1;
+            return greeting + name;
+        }
+        println(greet(\"amy\"));
+        println(greet(\"bob\"));
+        println(inttostr(calls));
+    ");
+    assert_eq!(output.unwrap(), "hi amy\nhi bob\n2\n");
+}
+
+#[test]
+fn functions_do_not_see_the_locals_of_their_caller() {
+    let result = run("
+        function show(): int {
+            return secret;
+        }
+        function caller(): int {
+            let secret = 1;
+            return show();
+        }
+        println(inttostr(caller()));
+    ");
+    assert!(matches!(
+        result,
+        Err(Error::Analysis(AnalysisError::UndefinedVariable { ref expected, .. })) if expected == "secret"
+    ));
+}
+
+#[test]
+fn parameters_shadow_global_variables() {
+    let output = run("
+        let n = 100;
+        function double(n: int): int {
+            return n * 2;
+        }
+        println(inttostr(double(4)));
+        println(inttostr(n));
+    ");
+    assert_eq!(output.unwrap(), "8\n100\n");
+}
@@ -0,0 +1,256 @@
+use aryalang::core::parser::ast::{Expression, Statement, Type};
+use aryalang::core::semantic_analyzer::defination::AnalysisError;
+use aryalang::{Aryalang, Error, StreamIo};
+use std::io::Cursor;
+use std::sync::{Arc, Mutex};
+
+fn check(source: &str) -> Result<Vec<Statement>, Error> {
+    Aryalang::new(source.to_string()).check()
+}
+
+// The type recorded for the initializer of every `let`, in order.
+fn let_types(statements: &[Statement]) -> Vec<(String, Option<Type>)> {
+    statements
+        .iter()
+        .filter_map(|statement| match statement {
+            Statement::Let(name, _, expression, _) => {
+                Some((name.clone(), expression.expression_type().cloned()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn annotations_are_parsed() {
+    let statements = check("let count: int = 0; let names: [string] = [];").unwrap();
+    let annotations: Vec<Option<Type>> = statements
+        .iter()
+        .map(|statement| match statement {
+            Statement::Let(_, annotation, ..) => annotation.clone(),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        annotations,
+        vec![
+            Some(Type::Integer),
+            Some(Type::Array(Box::new(Type::String)))
+        ]
+    );
+}
+
+#[test]
+fn annotation_gives_an_empty_array_its_element_type() {
+    let statements = check("let names: [string] = []; names[0] = \"amy\";").unwrap();
+    assert_eq!(
+        let_types(&statements),
+        vec![(
+            "names".to_string(),
+            Some(Type::Array(Box::new(Type::String)))
+        )]
+    );
+}
+
+#[test]
+fn annotation_that_does_not_match_the_initializer_is_an_error() {
+    assert!(matches!(
+        check("let count: int = \"zero\";"),
+        Err(Error::Analysis(AnalysisError::MismatchedTypes { .. }))
+    ));
+    assert!(matches!(
+        check("let names: [string] = []; names[0] = 1;"),
+        Err(Error::Analysis(AnalysisError::IllegalOperation { .. }))
+    ));
+}
+
+#[test]
+fn every_expression_gets_its_inferred_type() {
+    let statements = check(
+        "
+        let a = 1 + 2;
+        let b = 2.5;
+        let c = \"x\" + \"y\";
+        let d = a < 3 && true;
+        let e = [[1], []];
+        let f = strlen(c);
+        ",
+    )
+    .unwrap();
+    assert_eq!(
+        let_types(&statements),
+        vec![
+            ("a".to_string(), Some(Type::Integer)),
+            ("b".to_string(), Some(Type::Decimal)),
+            ("c".to_string(), Some(Type::String)),
+            ("d".to_string(), Some(Type::Bool)),
+            (
+                "e".to_string(),
+                Some(Type::Array(Box::new(Type::Array(Box::new(Type::Integer)))))
+            ),
+            ("f".to_string(), Some(Type::Integer)),
+        ]
+    );
+
+    // Operands are typed too, not just the outermost expression.
+    let Statement::Let(_, _, expression, _) = &statements[3] else {
+        panic!("expected a let statement");
+    };
+    let Expression::BinOp(left, _, right, _, _) = expression.as_ref() else {
+        panic!("expected a binary operation");
+    };
+    assert_eq!(left.expression_type(), Some(&Type::Bool));
+    assert_eq!(right.expression_type(), Some(&Type::Bool));
+}
+
+#[test]
+fn expressions_inside_untaken_branches_and_functions_are_typed() {
+    let statements = check(
+        "
+        function half(n: int): int {
+            return n / 2;
+        }
+        if false {
+            println(\"never\");
+        }
+        ",
+    )
+    .unwrap();
+    let Statement::FunctionDeclaration(_, _, body, _, _) = &statements[0] else {
+        panic!("expected a function declaration");
+    };
+    let Statement::Return(Some(expression), _) = &body[0] else {
+        panic!("expected a return statement");
+    };
+    assert_eq!(expression.expression_type(), Some(&Type::Integer));
+
+    let Statement::IfStatement(_, branch, _, _) = &statements[1] else {
+        panic!("expected an if statement");
+    };
+    let Statement::ExpressionStatement(call, _) = &branch[0] else {
+        panic!("expected an expression statement");
+    };
+    assert_eq!(call.expression_type(), Some(&Type::Void));
+}
+
+#[test]
+fn mixed_integer_and_decimal_elements_are_decimals() {
+    let statements =
+        check("let a = [1, 2.5]; let b: [float] = [1, 2]; let c = [[1], [2.5]];").unwrap();
+    assert_eq!(
+        let_types(&statements),
+        vec![
+            ("a".to_string(), Some(Type::Array(Box::new(Type::Decimal)))),
+            ("b".to_string(), Some(Type::Array(Box::new(Type::Decimal)))),
+            (
+                "c".to_string(),
+                Some(Type::Array(Box::new(Type::Array(Box::new(Type::Decimal)))))
+            ),
+        ]
+    );
+}
+
+#[test]
+fn errors_in_code_that_never_runs_are_reported() {
+    for source in [
+        "if false { let x: int = \"one\"; }",
+        "if true { } else { let x: int = \"one\"; }",
+        "while false { let x: int = \"one\"; }",
+        "let i = 0; for (i = 0; i < 0; i = i + 1) { let x: int = \"one\"; }",
+        "function unused() { let x: int = \"one\"; }",
+    ] {
+        assert!(
+            matches!(
+                check(source),
+                Err(Error::Analysis(AnalysisError::MismatchedTypes { .. }))
+            ),
+            "{}",
+            source
+        );
+    }
+}
+
+#[test]
+fn checking_does_not_run_anything() {
+    let io = Arc::new(Mutex::new(StreamIo::new(
+        Cursor::new(b"typed\n".to_vec()),
+        Vec::new(),
+    )));
+    let source = "println(\"hi\"); let name = input(); print(name);";
+    Aryalang::with_io(source.to_string(), io.clone())
+        .check()
+        .unwrap();
+    let io = io.lock().unwrap();
+    assert!(io.writer.is_empty());
+    assert_eq!(io.reader.position(), 0);
+}
+
+fn array_of(element_type: Type) -> Type {
+    Type::Array(Box::new(element_type))
+}
+
+#[test]
+fn arrays_take_the_type_of_their_elements() {
+    let statements = check(
+        "let a = [1, 2];
+        let b = [[1], [2, 3]];
+        let c = [[], [\"x\"]];
+        let d = [[1], []];
+        let e = [[[true]]];",
+    )
+    .unwrap();
+    assert_eq!(
+        let_types(&statements),
+        vec![
+            ("a".to_string(), Some(array_of(Type::Integer))),
+            ("b".to_string(), Some(array_of(array_of(Type::Integer)))),
+            ("c".to_string(), Some(array_of(array_of(Type::String)))),
+            ("d".to_string(), Some(array_of(array_of(Type::Integer)))),
+            (
+                "e".to_string(),
+                Some(array_of(array_of(array_of(Type::Bool))))
+            ),
+        ]
+    );
+}
+
+#[test]
+fn empty_arrays_can_hold_anything() {
+    let statements = check("let a = [];").unwrap();
+    assert_eq!(
+        let_types(&statements),
+        vec![("a".to_string(), Some(array_of(Type::Any)))]
+    );
+}
+
+#[test]
+fn elements_of_different_types_are_an_error() {
+    for source in [
+        "let x = [1, \"a\"];",
+        "let x = [[1], [\"a\"]];",
+        "let x = [true, [true]];",
+    ] {
+        assert!(
+            matches!(
+                check(source),
+                Err(Error::Analysis(AnalysisError::MismatchedTypes { .. }))
+            ),
+            "{}",
+            source
+        );
+    }
+}
+
+#[test]
+fn functions_cannot_be_called_before_the_variables_they_read() {
+    let error = check(
+        "println(\"start\"); println(inttostr(f())); let g = 1; function f(): int { return g; }",
+    )
+    .unwrap_err();
+    assert!(matches!(
+        error,
+        Error::Analysis(AnalysisError::UndefinedFunction { found, .. }) if found == "f"
+    ));
+    // After the `let`, it can be.
+    assert!(check("let g = 1; println(inttostr(f())); function f(): int { return g; }").is_ok());
+}